@@ -0,0 +1,179 @@
+use crate::cards::{Card, CardType};
+use crate::constants::CARD_SLOT_COUNT;
+use crate::game_state::{CardSlot, CardSlotType, PlayerActionEvent, Team, TurnState};
+use crate::game_ui_controller::GameController;
+use crate::launch_options::LaunchOptions;
+use crate::protocol::PlayerAction;
+use crate::rules::MatchSeed;
+use bevy::prelude::*;
+use bevy_rand::prelude::WyRand;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+const THINK_SECONDS: f32 = 0.6;
+
+// cards that deal no damage on their own are sent back during the mulligan
+fn should_return(card: &Card) -> bool {
+    card.damage.unwrap_or(0) == 0
+}
+
+fn get_hand_card<'a>(
+    game_ui_controller: &GameController,
+    team: Team,
+    id: usize,
+    cards: &'a Assets<Card>,
+) -> Option<&'a Card> {
+    let slot = CardSlot {
+        id,
+        team,
+        slot_type: CardSlotType::Hand,
+    };
+    game_ui_controller
+        .get_card(&slot)
+        .and_then(|(card, _)| cards.get(card))
+}
+
+// cards without hp can never be destroyed and would block their lane for good
+fn count_unbreakable(game_ui_controller: &GameController, team: Team) -> usize {
+    (0..CARD_SLOT_COUNT)
+        .filter_map(|id| {
+            game_ui_controller.get_card(&CardSlot {
+                id,
+                team,
+                slot_type: CardSlotType::Play,
+            })
+        })
+        .filter(|(_, stats)| stats.hp.is_none())
+        .count()
+}
+
+// the next action for a computer controlled team, None while it has nothing to do
+pub fn choose_action(
+    game_ui_controller: &GameController,
+    team: Team,
+    cards: &Assets<Card>,
+    rng: &mut impl Rng,
+) -> Option<PlayerAction> {
+    if game_ui_controller.get_current_team() != team {
+        return None;
+    }
+    match game_ui_controller.get_turn_state() {
+        TurnState::Mulligan => {
+            let hand_slots = (0..CARD_SLOT_COUNT)
+                .filter(|id| {
+                    get_hand_card(game_ui_controller, team, *id, cards)
+                        .map(should_return)
+                        .unwrap_or(false)
+                })
+                .collect();
+            Some(PlayerAction::Mulligan { hand_slots })
+        }
+        // makes room in a full hand by dropping its weakest card
+        TurnState::DrawCards if game_ui_controller.card_stack_full(team, CardSlotType::Hand) => {
+            let hand_slot = (0..CARD_SLOT_COUNT)
+                .min_by_key(|id| {
+                    get_hand_card(game_ui_controller, team, *id, cards)
+                        .and_then(|x| x.damage)
+                        .unwrap_or(0)
+                })
+                .unwrap();
+            Some(PlayerAction::Discard { hand_slot })
+        }
+        TurnState::DrawCards => {
+            let pile = game_ui_controller
+                .get_next_card_types(team)
+                .iter()
+                .position(|x| [CardType::Hero, CardType::Beast].contains(x))
+                .unwrap_or(0);
+            Some(PlayerAction::Draw { pile })
+        }
+        // tries the placements in a random order on a copy of the board and plays the first legal one
+        TurnState::PlayCards => {
+            let mut placements: Vec<PlayerAction> = (0..CARD_SLOT_COUNT)
+                .flat_map(|hand_slot| {
+                    (0..CARD_SLOT_COUNT).map(move |play_slot| PlayerAction::Place {
+                        hand_slot,
+                        play_slot,
+                    })
+                })
+                .collect();
+            placements.shuffle(rng);
+            let unbreakable = count_unbreakable(game_ui_controller, team);
+            for placement in placements {
+                let mut trial = game_ui_controller.clone();
+                if trial.apply_action(team, &placement, cards, rng).is_ok()
+                    && count_unbreakable(&trial, team) <= unbreakable
+                {
+                    return Some(placement);
+                }
+            }
+            Some(PlayerAction::EndTurn)
+        }
+        TurnState::ApplyMoves => None,
+    }
+}
+
+// the computer opponent of a local match, it has its own rng so the match draws stay the same
+#[derive(Resource, Clone, Debug)]
+pub struct AiPlayer {
+    pub team: Team,
+    rng: WyRand,
+    timer: Timer,
+}
+
+fn start_ai_player(
+    mut commands: Commands,
+    launch_options: Res<LaunchOptions>,
+    seed: Res<MatchSeed>,
+) {
+    if !launch_options.has_ai_player() {
+        return;
+    }
+    commands.insert_resource(AiPlayer {
+        team: launch_options.ai_team.unwrap(),
+        rng: WyRand::from_seed(seed.0.to_le_bytes()),
+        timer: Timer::from_seconds(THINK_SECONDS, TimerMode::Repeating),
+    });
+}
+
+fn play_ai_turn(
+    time: Res<Time>,
+    ai_player: Option<ResMut<AiPlayer>>,
+    game_ui_controller_query: Query<&GameController>,
+    cards: Res<Assets<Card>>,
+    mut player_actions: EventWriter<PlayerActionEvent>,
+) {
+    let mut ai_player = match ai_player {
+        Some(x) => x,
+        None => {
+            return;
+        }
+    };
+    let game_ui_controller = match game_ui_controller_query.get_single() {
+        Ok(x) => x,
+        _ => {
+            return;
+        }
+    };
+    let team = ai_player.team;
+    if game_ui_controller.get_current_team() != team {
+        ai_player.timer.reset();
+        return;
+    }
+    // waits a moment before every action so the moves can be followed on screen
+    if !ai_player.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    if let Some(action) = choose_action(game_ui_controller, team, &cards, &mut ai_player.rng) {
+        player_actions.send(PlayerActionEvent { team, action });
+    }
+}
+
+pub struct AiPlugin;
+
+impl Plugin for AiPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, start_ai_player)
+            .add_systems(Update, play_ai_turn);
+    }
+}
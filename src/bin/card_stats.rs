@@ -0,0 +1,81 @@
+// prints how the cards are spread over types, colors and stats
+// cargo run --bin card_stats -- --format csv --out card_stats.csv
+use project_color_card::card_stats::CardStatsReport;
+use project_color_card::cards::read_card_files;
+use std::path::PathBuf;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum OutputFormat {
+    Markdown,
+    Csv,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+struct CardStatsOptions {
+    cards: PathBuf,
+    format: OutputFormat,
+    out: Option<PathBuf>,
+}
+
+impl CardStatsOptions {
+    fn from_args(args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut options = CardStatsOptions {
+            cards: PathBuf::from("assets/cards"),
+            format: OutputFormat::Markdown,
+            out: None,
+        };
+        let mut args = args.skip(1);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--cards" => {
+                    options.cards = PathBuf::from(
+                        args.next()
+                            .ok_or("--cards expects the folder of .card.ron files")?,
+                    )
+                }
+                "--format" => {
+                    options.format = match args.next().as_deref() {
+                        Some("markdown") | Some("md") => OutputFormat::Markdown,
+                        Some("csv") => OutputFormat::Csv,
+                        _ => return Err("--format expects markdown or csv".to_string()),
+                    }
+                }
+                "--out" => {
+                    options.out = Some(PathBuf::from(
+                        args.next().ok_or("--out expects a file to write")?,
+                    ))
+                }
+                _ => return Err(format!("unknown argument {}", arg)),
+            }
+        }
+        Ok(options)
+    }
+}
+
+fn run(options: CardStatsOptions) -> Result<(), String> {
+    let report = CardStatsReport::new(&read_card_files(&options.cards)?);
+    let text = match options.format {
+        OutputFormat::Markdown => report.to_markdown(),
+        OutputFormat::Csv => report.to_csv()?,
+    };
+    match &options.out {
+        Some(path) => {
+            std::fs::write(path, text).map_err(|err| format!("{}: {}", path.display(), err))
+        }
+        None => {
+            print!("{}", text);
+            Ok(())
+        }
+    }
+}
+
+fn main() {
+    let result = CardStatsOptions::from_args(std::env::args()).and_then(run);
+    match result {
+        Ok(_) => {}
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    }
+}
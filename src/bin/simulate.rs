@@ -0,0 +1,137 @@
+// plays bot against bot matches over a range of seeds and writes how every card fared
+// cargo run --bin simulate -- --seeds 0..1000 --format json --out balance.json
+use bevy::prelude::*;
+use project_color_card::cards::{read_card_files, Card};
+use project_color_card::rules::{load_match_rules, MatchRules};
+use project_color_card::simulation::{Simulation, SimulationReport, DEFAULT_MAX_TURNS};
+use std::ops::Range;
+use std::path::PathBuf;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum OutputFormat {
+    Csv,
+    Json,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+struct SimulateOptions {
+    seeds: Range<u64>,
+    cards: PathBuf,
+    rules: Option<PathBuf>,
+    max_turns: u32,
+    format: OutputFormat,
+    out: Option<PathBuf>,
+}
+
+impl SimulateOptions {
+    fn from_args(args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut options = SimulateOptions {
+            seeds: 0..100,
+            cards: PathBuf::from("assets/cards"),
+            rules: None,
+            max_turns: DEFAULT_MAX_TURNS,
+            format: OutputFormat::Csv,
+            out: None,
+        };
+        let mut args = args.skip(1);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--seeds" => {
+                    options.seeds = args
+                        .next()
+                        .and_then(|x| {
+                            let (start, end) = x.split_once("..")?;
+                            Some(start.parse().ok()?..end.parse().ok()?)
+                        })
+                        .ok_or("--seeds expects a range such as 0..1000")?
+                }
+                "--cards" => {
+                    options.cards = PathBuf::from(
+                        args.next()
+                            .ok_or("--cards expects the folder of .card.ron files")?,
+                    )
+                }
+                "--rules" => {
+                    options.rules = Some(PathBuf::from(
+                        args.next().ok_or("--rules expects a .ron file")?,
+                    ))
+                }
+                "--max-turns" => {
+                    options.max_turns = args
+                        .next()
+                        .and_then(|x| x.parse().ok())
+                        .ok_or("--max-turns expects a number")?
+                }
+                "--format" => {
+                    options.format = match args.next().as_deref() {
+                        Some("csv") => OutputFormat::Csv,
+                        Some("json") => OutputFormat::Json,
+                        _ => return Err("--format expects csv or json".to_string()),
+                    }
+                }
+                "--out" => {
+                    options.out = Some(PathBuf::from(
+                        args.next().ok_or("--out expects a file to write")?,
+                    ))
+                }
+                _ => return Err(format!("unknown argument {}", arg)),
+            }
+        }
+        Ok(options)
+    }
+}
+
+fn format_report(report: &SimulationReport, format: OutputFormat) -> Result<String, String> {
+    match format {
+        OutputFormat::Json => serde_json::to_string_pretty(report).map_err(|err| err.to_string()),
+        OutputFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(vec![]);
+            for card in report.cards.iter() {
+                writer.serialize(card).map_err(|err| err.to_string())?;
+            }
+            let bytes = writer.into_inner().map_err(|err| err.to_string())?;
+            String::from_utf8(bytes).map_err(|err| err.to_string())
+        }
+    }
+}
+
+fn run(options: SimulateOptions) -> Result<(), String> {
+    let mut cards = Assets::<Card>::default();
+    for card in read_card_files(&options.cards)? {
+        cards.add(card);
+    }
+    let rules = match &options.rules {
+        Some(path) => load_match_rules(path),
+        None => MatchRules::default(),
+    };
+    let mut simulation = Simulation::new(&cards);
+    for seed in options.seeds.clone() {
+        simulation.run_match(seed, &cards, &rules, options.max_turns);
+    }
+    let report = simulation.report();
+    eprintln!(
+        "{} matches, red won {}, blue won {}, {} draws",
+        report.matches, report.red_wins, report.blue_wins, report.draws
+    );
+    let text = format_report(&report, options.format)?;
+    match &options.out {
+        Some(path) => {
+            std::fs::write(path, text).map_err(|err| format!("{}: {}", path.display(), err))
+        }
+        None => {
+            print!("{}", text);
+            Ok(())
+        }
+    }
+}
+
+fn main() {
+    let result = SimulateOptions::from_args(std::env::args()).and_then(run);
+    match result {
+        Ok(_) => {}
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    }
+}
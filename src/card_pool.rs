@@ -0,0 +1,254 @@
+use crate::cards::{Card, CardType};
+use crate::rules::RarityWeights;
+use bevy::prelude::*;
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::Rng;
+use std::collections::HashMap;
+use std::fmt;
+
+// cards with more colors only come from combining, they are never drawn
+const MAX_DRAWN_COLORS: usize = 2;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CardPoolError {
+    Empty,
+    EmptyBucket {
+        card_type: CardType,
+        color_count: usize,
+    },
+}
+
+impl fmt::Display for CardPoolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CardPoolError::Empty => write!(f, "there are no cards to draw"),
+            CardPoolError::EmptyBucket {
+                card_type,
+                color_count,
+            } => write!(
+                f,
+                "there is no {:?} card with {} colors",
+                card_type, color_count
+            ),
+        }
+    }
+}
+
+// card ids with their draw weights
+type WeightedCards = Vec<(AssetId<Card>, u32)>;
+
+#[derive(Clone, Debug)]
+struct Bucket {
+    ids: Vec<AssetId<Card>>,
+    weights: WeightedIndex<u32>,
+}
+
+impl Bucket {
+    // None when nothing in it could ever be picked
+    fn new(cards: WeightedCards) -> Option<Self> {
+        let weights = WeightedIndex::new(cards.iter().map(|x| x.1)).ok()?;
+        Some(Bucket {
+            ids: cards.into_iter().map(|x| x.0).collect(),
+            weights,
+        })
+    }
+
+    fn sample(&self, rng: &mut impl Rng) -> AssetId<Card> {
+        self.ids[self.weights.sample(rng)]
+    }
+}
+
+// the drawable cards indexed once by type and color count, so a draw never has to search
+#[derive(Clone, Debug)]
+pub struct CardPool {
+    all: Option<Bucket>,
+    buckets: HashMap<(CardType, usize), Bucket>,
+}
+
+impl CardPool {
+    // the cards are expected in a stable order so a seeded match draws the same cards
+    pub fn new(cards: &[(AssetId<Card>, &Card)], rarity_weights: &RarityWeights) -> Self {
+        let drawable: Vec<(AssetId<Card>, &Card)> = cards
+            .iter()
+            .filter(|(_, card)| card.colors.len() <= MAX_DRAWN_COLORS)
+            .copied()
+            .collect();
+        let mut grouped: HashMap<(CardType, usize), WeightedCards> = HashMap::new();
+        for (id, card) in drawable.iter() {
+            grouped
+                .entry((card.card_type, card.colors.len()))
+                .or_default()
+                .push((*id, rarity_weights.get_weight(card.rarity)));
+        }
+        CardPool {
+            all: Bucket::new(
+                drawable
+                    .iter()
+                    .map(|(id, card)| (*id, rarity_weights.get_weight(card.rarity)))
+                    .collect(),
+            ),
+            buckets: grouped
+                .into_iter()
+                .filter_map(|(key, cards)| Some((key, Bucket::new(cards)?)))
+                .collect(),
+        }
+    }
+
+    pub fn sample(&self, rng: &mut impl Rng) -> Result<AssetId<Card>, CardPoolError> {
+        match &self.all {
+            Some(bucket) => Ok(bucket.sample(rng)),
+            None => Err(CardPoolError::Empty),
+        }
+    }
+
+    pub fn sample_of_type_with_len(
+        &self,
+        rng: &mut impl Rng,
+        card_type: CardType,
+        color_count: usize,
+    ) -> Result<AssetId<Card>, CardPoolError> {
+        match self.buckets.get(&(card_type, color_count)) {
+            Some(bucket) => Ok(bucket.sample(rng)),
+            None => Err(CardPoolError::EmptyBucket {
+                card_type,
+                color_count,
+            }),
+        }
+    }
+
+    // the drawable color counts of a type, in order
+    pub fn get_color_counts(&self, card_type: CardType) -> Vec<usize> {
+        (1..=MAX_DRAWN_COLORS)
+            .filter(|x| self.buckets.contains_key(&(card_type, *x)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::asset::ron;
+    use bevy_rand::prelude::WyRand;
+    use rand::SeedableRng;
+
+    const DRAWS: usize = 4000;
+
+    // (type, color count, rarity) in the order the pool gets them
+    fn get_pool(
+        placed: &[(&str, usize, &str)],
+        rarity_weights: &RarityWeights,
+    ) -> (CardPool, Vec<AssetId<Card>>) {
+        let colors = ["Red", "Blue"];
+        let mut cards = Assets::<Card>::default();
+        let ids: Vec<AssetId<Card>> = placed
+            .iter()
+            .enumerate()
+            .map(|(index, (card_type, color_count, rarity))| {
+                let card: Card = ron::de::from_str(&format!(
+                    "Card(name: \"{}\", colors: [{}], card_type: {}, text: \"\", image: \"\", rarity: {})",
+                    index,
+                    colors[..*color_count].join(", "),
+                    card_type,
+                    rarity
+                ))
+                .unwrap();
+                cards.add(card).id()
+            })
+            .collect();
+        let sorted: Vec<(AssetId<Card>, &Card)> = ids
+            .iter()
+            .map(|id| (*id, cards.get(*id).unwrap()))
+            .collect();
+        (CardPool::new(&sorted, rarity_weights), ids)
+    }
+
+    fn count_draws(
+        pool: &CardPool,
+        card_type: CardType,
+        color_count: usize,
+    ) -> HashMap<AssetId<Card>, usize> {
+        let mut rng = WyRand::seed_from_u64(0);
+        let mut counts = HashMap::new();
+        for _ in 0..DRAWS {
+            let card = pool
+                .sample_of_type_with_len(&mut rng, card_type, color_count)
+                .unwrap();
+            *counts.entry(card).or_default() += 1;
+        }
+        counts
+    }
+
+    #[test]
+    fn empty_pool_and_bucket_are_errors() {
+        let (pool, _) = get_pool(&[], &RarityWeights::default());
+        let mut rng = WyRand::seed_from_u64(0);
+        assert_eq!(pool.sample(&mut rng), Err(CardPoolError::Empty));
+        let (pool, _) = get_pool(&[("Hero", 1, "Common")], &RarityWeights::default());
+        assert_eq!(
+            pool.sample_of_type_with_len(&mut rng, CardType::Beast, 1),
+            Err(CardPoolError::EmptyBucket {
+                card_type: CardType::Beast,
+                color_count: 1,
+            })
+        );
+        assert_eq!(
+            pool.sample_of_type_with_len(&mut rng, CardType::Hero, 2),
+            Err(CardPoolError::EmptyBucket {
+                card_type: CardType::Hero,
+                color_count: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn draws_follow_the_rarity_weights() {
+        let weights = RarityWeights {
+            common: 3,
+            uncommon: 1,
+            rare: 0,
+            legendary: 0,
+        };
+        let (pool, ids) = get_pool(
+            &[
+                ("Hero", 1, "Common"),
+                ("Hero", 1, "Uncommon"),
+                ("Hero", 1, "Rare"),
+            ],
+            &weights,
+        );
+        let counts = count_draws(&pool, CardType::Hero, 1);
+        let common = counts.get(&ids[0]).copied().unwrap_or(0);
+        assert!((2800..3200).contains(&common), "drew {} commons", common);
+        assert_eq!(counts.get(&ids[2]), None);
+    }
+
+    #[test]
+    fn the_last_card_of_a_bucket_can_be_drawn() {
+        let (pool, ids) = get_pool(
+            &[
+                ("Hero", 1, "Common"),
+                ("Hero", 1, "Common"),
+                ("Hero", 1, "Legendary"),
+            ],
+            &RarityWeights::default(),
+        );
+        let counts = count_draws(&pool, CardType::Hero, 1);
+        assert!(ids.iter().all(|id| counts.contains_key(id)));
+        let weights = RarityWeights {
+            common: 0,
+            ..default()
+        };
+        let (pool, ids) = get_pool(
+            &[
+                ("Hero", 1, "Common"),
+                ("Hero", 1, "Common"),
+                ("Hero", 1, "Legendary"),
+            ],
+            &weights,
+        );
+        assert_eq!(
+            count_draws(&pool, CardType::Hero, 1).get(&ids[2]),
+            Some(&DRAWS)
+        );
+    }
+}
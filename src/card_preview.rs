@@ -0,0 +1,272 @@
+use crate::assets::LoadState;
+use crate::cards::Card;
+use crate::custom_cursor::CustomCursor;
+use crate::equipment::EquippedCard;
+use crate::game_state::{CardSlot, CardSlotMarker, CardStats, CurrentTurnTeam};
+use crate::game_ui_controller::GameController;
+use crate::hot_seat::is_face_down;
+use crate::launch_options::LaunchOptions;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+const PREVIEW_WIDTH: f32 = 216.0;
+const PREVIEW_IMAGE_HEIGHT: f32 = PREVIEW_WIDTH * 102.0 / 72.0;
+const PREVIEW_PADDING: f32 = 8.0;
+const PREVIEW_CURSOR_OFFSET: f32 = 24.0;
+// rough upper bound of the text block under the image, used to keep the panel on screen
+const PREVIEW_TEXT_HEIGHT: f32 = 260.0;
+
+#[derive(Component, Clone, PartialEq, Eq, PartialOrd, Ord, Reflect)]
+pub struct CardPreviewMarker;
+
+#[derive(Component, Clone, PartialEq, Eq, PartialOrd, Ord, Reflect)]
+pub struct CardPreviewImageMarker;
+
+#[derive(Component, Clone, PartialEq, Eq, PartialOrd, Ord, Reflect)]
+pub struct CardPreviewTextMarker;
+
+fn spawn_card_preview(mut commands: Commands, assets: Res<AssetServer>) {
+    let font: Handle<Font> = assets.load("ui/simple-pixel.ttf");
+    let text_style = TextStyle {
+        font: font.clone(),
+        font_size: 14.0,
+        color: Color::WHITE,
+    };
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Px(PREVIEW_WIDTH + PREVIEW_PADDING * 2.0),
+                padding: UiRect::all(Val::Px(PREVIEW_PADDING)),
+                flex_direction: FlexDirection::Column,
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+            background_color: BackgroundColor(Color::rgba(0.08, 0.0, 0.07, 0.92)),
+            visibility: Visibility::Hidden,
+            z_index: ZIndex::Global(1),
+            ..default()
+        })
+        .insert(CardPreviewMarker)
+        .with_children(|parent| {
+            parent
+                .spawn(ImageBundle {
+                    style: Style {
+                        width: Val::Px(PREVIEW_WIDTH),
+                        height: Val::Px(PREVIEW_IMAGE_HEIGHT),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .insert(CardPreviewImageMarker);
+            parent
+                .spawn(
+                    TextBundle::from_sections([
+                        TextSection::new(
+                            "",
+                            TextStyle {
+                                font_size: 20.0,
+                                ..text_style.clone()
+                            },
+                        ),
+                        TextSection::new("", text_style.clone()),
+                        TextSection::new(
+                            "",
+                            TextStyle {
+                                color: Color::rgb(0.8, 0.8, 0.8),
+                                ..text_style.clone()
+                            },
+                        ),
+                    ])
+                    .with_style(Style {
+                        max_width: Val::Px(PREVIEW_WIDTH),
+                        margin: UiRect::top(Val::Px(PREVIEW_PADDING)),
+                        ..default()
+                    }),
+                )
+                .insert(CardPreviewTextMarker);
+        });
+}
+
+fn format_optional_stat(value: Option<u32>) -> String {
+    value.map(|x| x.to_string()).unwrap_or("-".to_string())
+}
+
+fn get_card_details(equipped: &EquippedCard) -> String {
+    let (card, stats) = (equipped.card, &equipped.stats);
+    let colors = card
+        .colors
+        .iter()
+        .map(|x| format!("{:?}", x))
+        .collect::<Vec<String>>()
+        .join(" / ");
+    let hp = match (stats.hp, stats.get_max_hp()) {
+        (Some(current), Some(max)) => format!("{}/{}", current, max),
+        (current, max) => format_optional_stat(current.or(max)),
+    };
+    // every keyword and trigger explains itself, so the preview doubles as its tooltip
+    let keywords: String = equipped
+        .get_keywords()
+        .map(|x| format!("\n{}", x.get_description()))
+        .chain(
+            card.triggers
+                .iter()
+                .map(|x| format!("\n{}", x.get_description())),
+        )
+        .collect();
+    let statuses: String = stats
+        .statuses
+        .iter()
+        .map(|x| format!("{}\n", x.get_description()))
+        .collect();
+    let buffs: String = stats
+        .buffs
+        .iter()
+        .map(|x| match x.turns {
+            Some(turns) => format!(
+                "Buff: +{} damage +{} hp for {} turns\n",
+                x.damage, x.hp, turns
+            ),
+            None => format!("Buff: +{} damage +{} hp\n", x.damage, x.hp),
+        })
+        .collect();
+    let equipment = match stats.equipment.is_empty() {
+        true => "".to_string(),
+        false => format!("Equipped: {}\n", stats.equipment.join(", ")),
+    };
+    format!(
+        "\n{:?} {:?}\nCost: {}\nColors: {}\nDamage: {}\nHP: {}\nHeal: {}\n{}{}{}{}",
+        card.rarity,
+        card.card_type,
+        card.get_cost(),
+        colors,
+        format_optional_stat(equipped.get_damage()),
+        hp,
+        format_optional_stat(card.heal_value),
+        statuses,
+        buffs,
+        equipment,
+        keywords,
+    )
+}
+
+type SlotButtonFilter = (With<Button>, With<CardSlotMarker>);
+
+// the card slot buttons the mouse can hover
+#[derive(SystemParam)]
+struct HoveredSlots<'w, 's> {
+    interaction_query: Query<'w, 's, (&'static Interaction, Entity), SlotButtonFilter>,
+    card_slot_query: Query<'w, 's, &'static CardSlot>,
+    children_query: Query<'w, 's, &'static Children>,
+    launch_options: Res<'w, LaunchOptions>,
+    current_turn_team: Res<'w, State<CurrentTurnTeam>>,
+}
+
+impl HoveredSlots<'_, '_> {
+    fn get_hovered_card(
+        &self,
+        game_ui_controller: &GameController,
+        custom_cursor: &CustomCursor,
+    ) -> Option<(AssetId<Card>, CardStats)> {
+        for (interaction, entity) in self.interaction_query.iter() {
+            if *interaction == Interaction::None {
+                continue;
+            }
+            let slot = match self
+                .children_query
+                .iter_descendants(entity)
+                .next()
+                .and_then(|x| self.card_slot_query.get(x).ok())
+            {
+                Some(x) => x,
+                None => continue,
+            };
+            if is_face_down(slot, &self.launch_options, self.current_turn_team.get().0) {
+                continue;
+            }
+            if let Some(card) = game_ui_controller.get_card(slot) {
+                return Some(card);
+            }
+        }
+        match custom_cursor {
+            CustomCursor::Card { card, stats, .. } => Some((*card, stats.clone())),
+            CustomCursor::Default => None,
+        }
+    }
+}
+
+#[derive(SystemParam)]
+struct CardPreviewNodes<'w, 's> {
+    panel_query:
+        Query<'w, 's, (&'static mut Style, &'static mut Visibility), With<CardPreviewMarker>>,
+    image_query: Query<'w, 's, &'static mut UiImage, With<CardPreviewImageMarker>>,
+    text_query: Query<'w, 's, &'static mut Text, With<CardPreviewTextMarker>>,
+}
+
+fn update_card_preview(
+    game_ui_controller_query: Query<&GameController>,
+    custom_cursor_query: Query<&CustomCursor>,
+    hovered_slots: HoveredSlots,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    cards: Res<Assets<Card>>,
+    mut preview: CardPreviewNodes,
+) {
+    let (mut style, mut visibility) = match preview.panel_query.get_single_mut() {
+        Ok(x) => x,
+        _ => {
+            return;
+        }
+    };
+    let (game_ui_controller, custom_cursor, window) = match (
+        game_ui_controller_query.get_single(),
+        custom_cursor_query.get_single(),
+        window_query.get_single(),
+    ) {
+        (Ok(x), Ok(y), Ok(z)) => (x, y, z),
+        _ => {
+            *visibility = Visibility::Hidden;
+            return;
+        }
+    };
+    let hovered = hovered_slots.get_hovered_card(game_ui_controller, custom_cursor);
+    let ((card, stats), cursor_position) = match (
+        hovered.and_then(|(id, stats)| cards.get(id).map(|card| (card, stats))),
+        window.cursor_position(),
+    ) {
+        (Some(x), Some(y)) => (x, y),
+        _ => {
+            *visibility = Visibility::Hidden;
+            return;
+        }
+    };
+
+    if let Ok(mut image) = preview.image_query.get_single_mut() {
+        image.texture = card.image_handle.clone();
+    }
+    if let Ok(mut text) = preview.text_query.get_single_mut() {
+        text.sections[0].value = card.name.clone();
+        text.sections[1].value = get_card_details(&game_ui_controller.equip(card, &stats, &cards));
+        text.sections[2].value = card.text.clone();
+    }
+
+    // place the panel beside the cursor, flipping to the other side when it would leave the window
+    let panel_width = PREVIEW_WIDTH + PREVIEW_PADDING * 2.0;
+    let panel_height = PREVIEW_IMAGE_HEIGHT + PREVIEW_TEXT_HEIGHT + PREVIEW_PADDING * 2.0;
+    let mut left = cursor_position.x + PREVIEW_CURSOR_OFFSET;
+    if left + panel_width > window.width() {
+        left = cursor_position.x - PREVIEW_CURSOR_OFFSET - panel_width;
+    }
+    let top = cursor_position.y - panel_height / 2.0;
+    style.left = Val::Px(left.clamp(0.0, (window.width() - panel_width).max(0.0)));
+    style.top = Val::Px(top.clamp(0.0, (window.height() - panel_height).max(0.0)));
+    *visibility = Visibility::Visible;
+}
+
+pub struct CardPreviewPlugin;
+
+impl Plugin for CardPreviewPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(LoadState::Loaded), spawn_card_preview)
+            .add_systems(Update, update_card_preview);
+    }
+}
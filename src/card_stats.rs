@@ -0,0 +1,288 @@
+use crate::cards::{Card, CardColor, CardRarity, CardType};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+// the longest histogram bar in the markdown table, the others are scaled to it
+const HISTOGRAM_WIDTH: u32 = 40;
+
+// how many cards of a type have this many colors, in total and per color
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ColorCountRow {
+    pub card_type: CardType,
+    pub color_count: usize,
+    pub total: u32,
+    pub per_color: Vec<(CardColor, u32)>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct RarityRow {
+    pub card_type: CardType,
+    pub per_rarity: Vec<(CardRarity, u32)>,
+}
+
+// one bar of a histogram, None counts the cards without the stat
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct HistogramBar {
+    pub card_type: CardType,
+    pub stat: &'static str,
+    pub value: Option<u32>,
+    pub count: u32,
+}
+
+// no two color card of the type has both colors, so a draw or combination asking for it finds nothing
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct MissingPair {
+    pub card_type: CardType,
+    pub first: CardColor,
+    pub second: CardColor,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct CardStatsReport {
+    pub card_count: usize,
+    pub color_counts: Vec<ColorCountRow>,
+    pub rarity_counts: Vec<RarityRow>,
+    pub histograms: Vec<HistogramBar>,
+    pub missing_pairs: Vec<MissingPair>,
+}
+
+// every table flattened into one csv, the columns a table does not use stay empty
+#[derive(Serialize, Clone, PartialEq, Eq, Debug)]
+struct CsvRow {
+    table: &'static str,
+    card_type: String,
+    color_count: Option<usize>,
+    color: Option<String>,
+    rarity: Option<CardRarity>,
+    stat: Option<&'static str>,
+    value: Option<String>,
+    count: u32,
+}
+
+fn format_value(value: Option<u32>) -> String {
+    match value {
+        Some(x) => x.to_string(),
+        None => "none".to_string(),
+    }
+}
+
+fn get_histogram(
+    cards: &[Card],
+    card_type: CardType,
+    stat: &'static str,
+    get_stat: fn(&Card) -> Option<u32>,
+) -> Vec<HistogramBar> {
+    let mut bars: BTreeMap<Option<u32>, u32> = BTreeMap::new();
+    for card in cards.iter().filter(|x| x.card_type == card_type) {
+        *bars.entry(get_stat(card)).or_default() += 1;
+    }
+    bars.into_iter()
+        .map(|(value, count)| HistogramBar {
+            card_type,
+            stat,
+            value,
+            count,
+        })
+        .collect()
+}
+
+impl CardStatsReport {
+    pub fn new(cards: &[Card]) -> Self {
+        let max_colors = cards.iter().map(|x| x.colors.len()).max().unwrap_or(0);
+        let mut color_counts = vec![];
+        let mut rarity_counts = vec![];
+        let mut histograms = vec![];
+        let mut missing_pairs = vec![];
+        for card_type in CardType::ALL {
+            let of_type: Vec<&Card> = cards.iter().filter(|x| x.card_type == card_type).collect();
+            for color_count in 1..=max_colors {
+                let with_len: Vec<&&Card> = of_type
+                    .iter()
+                    .filter(|x| x.colors.len() == color_count)
+                    .collect();
+                color_counts.push(ColorCountRow {
+                    card_type,
+                    color_count,
+                    total: with_len.len() as u32,
+                    per_color: CardColor::ALL
+                        .into_iter()
+                        .map(|color| {
+                            let count = with_len.iter().filter(|x| x.colors.contains(&color));
+                            (color.clone(), count.count() as u32)
+                        })
+                        .collect(),
+                });
+            }
+            rarity_counts.push(RarityRow {
+                card_type,
+                per_rarity: CardRarity::ALL
+                    .into_iter()
+                    .map(|rarity| {
+                        let count = of_type.iter().filter(|x| x.rarity == rarity).count();
+                        (rarity, count as u32)
+                    })
+                    .collect(),
+            });
+            histograms.extend(get_histogram(cards, card_type, "damage", |x| x.damage));
+            histograms.extend(get_histogram(cards, card_type, "hp", |x| x.hp));
+            // the pairs are unordered, a card with the colors either way round covers them
+            for (i, first) in CardColor::ALL.iter().enumerate() {
+                for second in CardColor::ALL[i + 1..].iter() {
+                    if !of_type.iter().any(|x| {
+                        x.colors.len() == 2 && x.colors.contains(first) && x.colors.contains(second)
+                    }) {
+                        missing_pairs.push(MissingPair {
+                            card_type,
+                            first: first.clone(),
+                            second: second.clone(),
+                        });
+                    }
+                }
+            }
+        }
+        CardStatsReport {
+            card_count: cards.len(),
+            color_counts,
+            rarity_counts,
+            histograms,
+            missing_pairs,
+        }
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("# Card stats\n\n{} cards\n\n", self.card_count);
+        out += "## Cards by type and colors\n\n| Type | Colors | Total |";
+        for color in CardColor::ALL {
+            out += &format!(" {:?} |", color);
+        }
+        out += "\n|---|---|---|";
+        out += &"---|".repeat(CardColor::ALL.len());
+        out += "\n";
+        for row in self.color_counts.iter() {
+            out += &format!(
+                "| {:?} | {} | {} |",
+                row.card_type, row.color_count, row.total
+            );
+            for (_, count) in row.per_color.iter() {
+                out += &format!(" {} |", count);
+            }
+            out += "\n";
+        }
+        out += "\n## Cards by type and rarity\n\n| Type |";
+        for rarity in CardRarity::ALL {
+            out += &format!(" {:?} |", rarity);
+        }
+        out += "\n|---|";
+        out += &"---|".repeat(CardRarity::ALL.len());
+        out += "\n";
+        for row in self.rarity_counts.iter() {
+            out += &format!("| {:?} |", row.card_type);
+            for (_, count) in row.per_rarity.iter() {
+                out += &format!(" {} |", count);
+            }
+            out += "\n";
+        }
+        let tallest = self.histograms.iter().map(|x| x.count).max().unwrap_or(1);
+        for stat in ["damage", "hp"] {
+            out += &format!(
+                "\n## {} by type\n\n| Type | Value | Cards |\n|---|---|---|\n",
+                stat
+            );
+            for bar in self.histograms.iter().filter(|x| x.stat == stat) {
+                out += &format!(
+                    "| {:?} | {} | {} {} |\n",
+                    bar.card_type,
+                    format_value(bar.value),
+                    bar.count,
+                    "#".repeat((bar.count * HISTOGRAM_WIDTH).div_ceil(tallest) as usize)
+                );
+            }
+        }
+        out += "\n## Color pairs without a two color card\n\n";
+        match self.missing_pairs.is_empty() {
+            true => out += "None, every type has every pair.\n",
+            false => {
+                out += "| Type | Colors |\n|---|---|\n";
+                for pair in self.missing_pairs.iter() {
+                    out += &format!(
+                        "| {:?} | {:?} + {:?} |\n",
+                        pair.card_type, pair.first, pair.second
+                    );
+                }
+            }
+        }
+        out
+    }
+
+    pub fn to_csv(&self) -> Result<String, String> {
+        let mut writer = csv::Writer::from_writer(vec![]);
+        let mut rows = vec![];
+        for row in self.color_counts.iter() {
+            rows.push(CsvRow {
+                table: "colors",
+                card_type: format!("{:?}", row.card_type),
+                color_count: Some(row.color_count),
+                color: None,
+                rarity: None,
+                stat: None,
+                value: None,
+                count: row.total,
+            });
+            for (color, count) in row.per_color.iter() {
+                rows.push(CsvRow {
+                    table: "colors",
+                    card_type: format!("{:?}", row.card_type),
+                    color_count: Some(row.color_count),
+                    color: Some(format!("{:?}", color)),
+                    rarity: None,
+                    stat: None,
+                    value: None,
+                    count: *count,
+                });
+            }
+        }
+        for row in self.rarity_counts.iter() {
+            for (rarity, count) in row.per_rarity.iter() {
+                rows.push(CsvRow {
+                    table: "rarity",
+                    card_type: format!("{:?}", row.card_type),
+                    color_count: None,
+                    color: None,
+                    rarity: Some(*rarity),
+                    stat: None,
+                    value: None,
+                    count: *count,
+                });
+            }
+        }
+        for bar in self.histograms.iter() {
+            rows.push(CsvRow {
+                table: "histogram",
+                card_type: format!("{:?}", bar.card_type),
+                color_count: None,
+                color: None,
+                rarity: None,
+                stat: Some(bar.stat),
+                value: Some(format_value(bar.value)),
+                count: bar.count,
+            });
+        }
+        for pair in self.missing_pairs.iter() {
+            rows.push(CsvRow {
+                table: "missing_pair",
+                card_type: format!("{:?}", pair.card_type),
+                color_count: Some(2),
+                color: Some(format!("{:?}+{:?}", pair.first, pair.second)),
+                rarity: None,
+                stat: None,
+                value: None,
+                count: 0,
+            });
+        }
+        for row in rows {
+            writer.serialize(row).map_err(|err| err.to_string())?;
+        }
+        let bytes = writer.into_inner().map_err(|err| err.to_string())?;
+        String::from_utf8(bytes).map_err(|err| err.to_string())
+    }
+}
@@ -1,4 +1,6 @@
 use crate::create_ron_nested_asset_loader;
+use crate::game_state::Status;
+use bevy::asset::ron;
 use bevy::{
     asset::{io::Reader, AssetLoader, LoadContext},
     prelude::*,
@@ -6,6 +8,7 @@ use bevy::{
     utils::BoxedFuture,
 };
 use serde::{Deserialize, Serialize};
+use std::path::Path;
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 #[repr(i32)]
 pub enum CardColor {
@@ -17,6 +20,28 @@ pub enum CardColor {
     Teal,
 }
 
+impl CardColor {
+    pub const ALL: [CardColor; 6] = [
+        CardColor::Red,
+        CardColor::Yellow,
+        CardColor::Blue,
+        CardColor::Green,
+        CardColor::Purple,
+        CardColor::Teal,
+    ];
+
+    pub fn ui_color(&self) -> Color {
+        match self {
+            CardColor::Red => Color::rgb(0.86, 0.2, 0.2),
+            CardColor::Yellow => Color::rgb(0.95, 0.84, 0.22),
+            CardColor::Blue => Color::rgb(0.22, 0.4, 0.9),
+            CardColor::Green => Color::rgb(0.27, 0.74, 0.3),
+            CardColor::Purple => Color::rgb(0.6, 0.3, 0.8),
+            CardColor::Teal => Color::rgb(0.2, 0.74, 0.72),
+        }
+    }
+}
+
 #[derive(
     Debug,
     Serialize,
@@ -42,6 +67,123 @@ pub enum CardType {
     Spell,
 }
 
+impl CardType {
+    pub const ALL: [CardType; 5] = [
+        CardType::Hero,
+        CardType::Beast,
+        CardType::Equipment,
+        CardType::Food,
+        CardType::Spell,
+    ];
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, Hash)]
+pub enum CardRarity {
+    #[default]
+    Common,
+    Uncommon,
+    Rare,
+    Legendary,
+}
+
+impl CardRarity {
+    pub const ALL: [CardRarity; 4] = [
+        CardRarity::Common,
+        CardRarity::Uncommon,
+        CardRarity::Rare,
+        CardRarity::Legendary,
+    ];
+
+    pub fn ui_color(&self) -> Color {
+        match self {
+            CardRarity::Common => Color::rgb(0.75, 0.75, 0.75),
+            CardRarity::Uncommon => Color::rgb(0.3, 0.8, 0.35),
+            CardRarity::Rare => Color::rgb(0.3, 0.55, 1.0),
+            CardRarity::Legendary => Color::rgb(1.0, 0.6, 0.1),
+        }
+    }
+}
+
+// abilities that change how a card fights, resolved with the rest of combat
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Keyword {
+    Taunt,
+    Lifesteal,
+    Piercing,
+    Regenerate(u32),
+    Flying,
+    Poison(u32),
+    Inflict(Status),
+}
+
+impl Keyword {
+    pub fn get_description(&self) -> String {
+        match self {
+            Keyword::Taunt => "Taunt: foes with no card in front of them next to it attack it instead of your team".to_string(),
+            Keyword::Lifesteal => "Lifesteal: heals your team by the damage it deals".to_string(),
+            Keyword::Piercing => "Piercing: damage beyond what the blocking card can take hits the other team".to_string(),
+            Keyword::Regenerate(x) => format!("Regenerate {}: heals {} hp at the end of every turn it survives", x, x),
+            Keyword::Flying => "Flying: only blocked by other flying cards".to_string(),
+            Keyword::Poison(x) => format!("Poison {}: cards it damages lose {} hp at the end of every turn", x, x),
+            Keyword::Inflict(x) => format!("Inflict: cards it damages become {}", x.get_description()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TriggerKind {
+    // once the turn the card was played in is ended
+    OnPlay,
+    OnDeath,
+    // at the start of each of its team's turns while it is in play
+    StartOfTurn,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Effect {
+    // hits the card across the lane, or the other team when the lane is empty
+    DamageOppositeLane(u32),
+    HealAdjacent(u32),
+    DrawCard,
+    // raises the hp of the neighbours that share a color with the card
+    BuffMatchingNeighbors(u32),
+    StatusAdjacent(Status),
+    StatusOppositeLane(Status),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Trigger {
+    pub when: TriggerKind,
+    pub effect: Effect,
+}
+
+impl Trigger {
+    pub fn get_description(&self) -> String {
+        let when = match self.when {
+            TriggerKind::OnPlay => "When played",
+            TriggerKind::OnDeath => "When destroyed",
+            TriggerKind::StartOfTurn => "At the start of your turn",
+        };
+        let effect = match self.effect {
+            Effect::DamageOppositeLane(x) => {
+                format!("deal {} to the card across, or the other team", x)
+            }
+            Effect::HealAdjacent(x) => format!("heal the cards next to it by {}", x),
+            Effect::DrawCard => "draw a card".to_string(),
+            Effect::BuffMatchingNeighbors(x) => {
+                format!("give the cards next to it that share a color {} hp", x)
+            }
+            Effect::StatusAdjacent(x) => {
+                format!("the cards next to it become {}", x.get_description())
+            }
+            Effect::StatusOppositeLane(x) => {
+                format!("the card across becomes {}", x.get_description())
+            }
+        };
+        format!("{}: {}", when, effect)
+    }
+}
+
 #[derive(Serialize, Deserialize, Asset, TypePath, Debug)]
 pub struct Card {
     pub name: String,
@@ -54,6 +196,23 @@ pub struct Card {
     pub damage: Option<u32>,
     pub hp: Option<u32>,
     pub heal_value: Option<u32>,
+    // cards that leave it out pay one energy per color
+    #[serde(default)]
+    pub cost: Option<u32>,
+    // cards that leave it out are common
+    #[serde(default)]
+    pub rarity: CardRarity,
+    #[serde(default)]
+    pub keywords: Vec<Keyword>,
+    #[serde(default)]
+    pub triggers: Vec<Trigger>,
+}
+
+impl Card {
+    // energy needed to play the card, cards without an explicit cost pay one per color
+    pub fn get_cost(&self) -> u32 {
+        self.cost.unwrap_or(self.colors.len() as u32)
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
@@ -88,6 +247,31 @@ create_ron_nested_asset_loader!(
     card_back_assets
 );
 
+// reads every .card.ron below the folder without going through the asset server, used by the tools
+pub fn read_card_files(folder: &Path) -> Result<Vec<Card>, String> {
+    let mut cards = vec![];
+    let entries =
+        std::fs::read_dir(folder).map_err(|err| format!("{}: {}", folder.display(), err))?;
+    for entry in entries {
+        let path = entry.map_err(|err| err.to_string())?.path();
+        if path.is_dir() {
+            cards.extend(read_card_files(&path)?);
+            continue;
+        }
+        if !path.to_string_lossy().ends_with(".card.ron") {
+            continue;
+        }
+        let text =
+            std::fs::read_to_string(&path).map_err(|err| format!("{}: {}", path.display(), err))?;
+        let card = ron::de::from_str::<Card>(&text)
+            .map_err(|err| format!("{}: {}", path.display(), err))?;
+        cards.push(card);
+    }
+    // directory order differs between systems, the tools should not
+    cards.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(cards)
+}
+
 pub fn get_card_back_image(
     card_backs: &Res<Assets<CardBack>>,
     back_type: CardBackType,
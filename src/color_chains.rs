@@ -0,0 +1,135 @@
+use crate::cards::Card;
+use crate::constants::CARD_SLOT_COUNT;
+use crate::game_state::{CardSlot, CardSlotType, ChainLinkMarker, Team};
+use crate::game_ui_controller::GameController;
+use bevy::prelude::*;
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+// the extra damage and hp a card gets from the chain it is part of
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct ChainBonusStats {
+    pub damage: u32,
+    pub hp: u32,
+}
+
+impl GameController {
+    fn get_play_card<'a>(
+        &self,
+        team: Team,
+        id: usize,
+        cards: &'a Assets<Card>,
+    ) -> Option<&'a Card> {
+        self.get_card(&CardSlot {
+            id,
+            team,
+            slot_type: CardSlotType::Play,
+        })
+        .map(|(card, _)| cards.get(card).unwrap())
+    }
+
+    // the same rule as placing a card, the left card's last color meets the right card's first
+    pub fn is_chain_link(&self, team: Team, id: usize, cards: &Assets<Card>) -> bool {
+        match (
+            self.get_play_card(team, id, cards),
+            self.get_play_card(team, id + 1, cards),
+        ) {
+            (Some(left), Some(right)) => left.colors.last() == right.colors.first(),
+            _ => false,
+        }
+    }
+
+    // the runs of linked cards in a play row that are long enough to count
+    pub fn get_chains(&self, team: Team, cards: &Assets<Card>) -> Vec<Range<usize>> {
+        let min_length = match &self.get_rules().chain_bonus {
+            Some(x) => x.min_length.max(2),
+            None => {
+                return vec![];
+            }
+        };
+        let mut chains = vec![];
+        let mut start = 0;
+        for id in 0..CARD_SLOT_COUNT {
+            if id + 1 < CARD_SLOT_COUNT && self.is_chain_link(team, id, cards) {
+                continue;
+            }
+            if id + 1 - start >= min_length {
+                chains.push(start..id + 1);
+            }
+            start = id + 1;
+        }
+        chains
+    }
+
+    // worked out once before a fight so cards dying mid fight do not change it
+    pub fn get_chain_bonuses(&self, cards: &Assets<Card>) -> BTreeMap<CardSlot, ChainBonusStats> {
+        let chain_bonus = match &self.get_rules().chain_bonus {
+            Some(x) => x.clone(),
+            None => {
+                return BTreeMap::new();
+            }
+        };
+        let mut bonuses = BTreeMap::new();
+        for team in [Team::Red, Team::Blue] {
+            for chain in self.get_chains(team, cards) {
+                let steps = (chain.len() + 1 - chain_bonus.min_length.max(2)) as u32;
+                for id in chain {
+                    bonuses.insert(
+                        CardSlot {
+                            id,
+                            team,
+                            slot_type: CardSlotType::Play,
+                        },
+                        ChainBonusStats {
+                            damage: chain_bonus.damage * steps,
+                            hp: chain_bonus.hp * steps,
+                        },
+                    );
+                }
+            }
+        }
+        bonuses
+    }
+}
+
+fn update_chain_links(
+    game_ui_controller_query: Query<&GameController>,
+    cards: Res<Assets<Card>>,
+    mut link_query: Query<(&ChainLinkMarker, &mut BackgroundColor)>,
+) {
+    let game_ui_controller = match game_ui_controller_query.get_single() {
+        Ok(x) => x,
+        _ => {
+            return;
+        }
+    };
+    let chains: BTreeMap<Team, Vec<Range<usize>>> = [Team::Red, Team::Blue]
+        .into_iter()
+        .map(|team| (team, game_ui_controller.get_chains(team, &cards)))
+        .collect();
+    for (link, mut background) in link_query.iter_mut() {
+        let in_chain = chains[&link.team]
+            .iter()
+            .any(|x| x.contains(&link.id) && x.contains(&(link.id + 1)));
+        // the line takes the color the two cards share
+        let color = match in_chain {
+            true => game_ui_controller
+                .get_play_card(link.team, link.id, &cards)
+                .and_then(|x| x.colors.last())
+                .map(|x| x.ui_color())
+                .unwrap_or(Color::NONE),
+            false => Color::NONE,
+        };
+        if background.0 != color {
+            background.0 = color;
+        }
+    }
+}
+
+pub struct ColorChainPlugin;
+
+impl Plugin for ColorChainPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, update_chain_links);
+    }
+}
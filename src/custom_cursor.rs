@@ -22,6 +22,7 @@ fn spawn_custom_cursor(mut commands: Commands, mut window: Query<&mut Window>) {
                 aspect_ratio: Some(72.0 / 102.0),
                 ..default()
             },
+            z_index: ZIndex::Global(10),
             ..default()
         })
         .insert(CustomCursor::Default);
@@ -0,0 +1,103 @@
+use crate::cards::{Card, CardType, Keyword};
+use crate::game_state::{CardSlot, CardStats, Status, StatusEffect};
+use crate::game_ui_controller::GameController;
+use bevy::prelude::*;
+
+// a card in play together with whatever its equipment, buffs and statuses change
+#[derive(Clone, Debug)]
+pub struct EquippedCard<'a> {
+    pub card: &'a Card,
+    pub equipment: Vec<&'a Card>,
+    pub stats: CardStats,
+}
+
+// the same color rule as combining, equipment has to bring colors the host lacks
+pub fn can_attach(host: &Card, equipment: &Card) -> bool {
+    [CardType::Hero, CardType::Beast].contains(&host.card_type)
+        && equipment.colors.iter().all(|x| !host.colors.contains(x))
+        && equipment.card_type == CardType::Equipment
+}
+
+impl<'a> EquippedCard<'a> {
+    // a card that deals no damage of its own still deals none
+    pub fn get_damage(&self) -> Option<u32> {
+        self.card.damage.map(|damage| {
+            (damage
+                + self.stats.get_buff_damage()
+                + self
+                    .equipment
+                    .iter()
+                    .map(|x| x.damage.unwrap_or(0))
+                    .sum::<u32>())
+            .saturating_sub(self.stats.get_weakness())
+        })
+    }
+
+    pub fn get_keywords(&self) -> impl Iterator<Item = &Keyword> {
+        self.card
+            .keywords
+            .iter()
+            .chain(self.equipment.iter().flat_map(|x| x.keywords.iter()))
+    }
+
+    pub fn has_keyword(&self, keyword: Keyword) -> bool {
+        self.get_keywords().any(|x| *x == keyword)
+    }
+
+    // the amount of Regenerate, 0 without it
+    pub fn get_regeneration(&self) -> u32 {
+        self.get_keywords()
+            .map(|x| match x {
+                Keyword::Regenerate(amount) => *amount,
+                _ => 0,
+            })
+            .sum()
+    }
+
+    // the statuses a card it damages is given, Poison lasts until that card dies
+    pub fn get_inflicted_statuses(&self) -> Vec<Status> {
+        self.get_keywords()
+            .filter_map(|x| match x {
+                Keyword::Poison(amount) => Some(Status {
+                    effect: StatusEffect::Poisoned(*amount),
+                    turns: None,
+                }),
+                Keyword::Inflict(status) => Some(*status),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl GameController {
+    // equipment is stored by name in the card's stats so it survives the network and replays
+    pub fn get_equipment<'a>(&self, stats: &CardStats, cards: &'a Assets<Card>) -> Vec<&'a Card> {
+        stats
+            .equipment
+            .iter()
+            .filter_map(|name| cards.get(self.find_card(name)?))
+            .collect()
+    }
+
+    pub fn equip<'a>(
+        &self,
+        card: &'a Card,
+        stats: &CardStats,
+        cards: &'a Assets<Card>,
+    ) -> EquippedCard<'a> {
+        EquippedCard {
+            card,
+            equipment: self.get_equipment(stats, cards),
+            stats: stats.clone(),
+        }
+    }
+
+    pub fn get_equipped_card<'a>(
+        &self,
+        slot: &CardSlot,
+        cards: &'a Assets<Card>,
+    ) -> Option<EquippedCard<'a>> {
+        self.get_card(slot)
+            .map(|(card, stats)| self.equip(cards.get(card).unwrap(), &stats, cards))
+    }
+}
@@ -0,0 +1,302 @@
+use crate::constants::CARD_SLOT_COUNT;
+use crate::custom_cursor::CustomCursor;
+use crate::game_state::{
+    CancelHeldCard, CardDeckMarker, CardDeckPressed, CardSlot, CardSlotMarker, CardSlotPressed,
+    CardSlotType, CurrentTurnTeam, DiscardMarker, DiscardPressed, EndTurnPressed, Team,
+    UndoPressed,
+};
+use crate::game_ui_controller::GameController;
+use crate::hot_seat::PassDevice;
+use crate::input_map::{ActionInputs, InputAction};
+use crate::match_log::MatchLog;
+use crate::settings_screen::{SettingsRow, SettingsRowPressed, SettingsScreen, SETTINGS_ROW_COUNT};
+use crate::spawn_ui::SLOT_ROWS;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+const FOCUS_COLOR: Color = Color::rgb(1.0, 0.9, 0.35);
+
+#[derive(Resource, Default, Clone, PartialEq, Eq, Debug)]
+pub enum Focus {
+    #[default]
+    None,
+    // one of the current team's draw piles
+    Deck(usize),
+    Discard,
+    Slot(CardSlot),
+    // a row of the settings screen while it is open
+    Setting(usize),
+}
+
+fn move_focus(focus: &Focus, action: InputAction, team: Team, piles: usize) -> Focus {
+    let current_hand = CardSlot {
+        id: 0,
+        team,
+        slot_type: CardSlotType::Hand,
+    };
+    match focus {
+        Focus::None | Focus::Setting(_) => Focus::Slot(current_hand),
+        Focus::Deck(pile) => match action {
+            InputAction::NextSlot if pile + 1 < piles => Focus::Deck(pile + 1),
+            InputAction::NextSlot => Focus::Slot(current_hand),
+            InputAction::PrevSlot => Focus::Deck(pile.saturating_sub(1)),
+            InputAction::NextRow => Focus::Discard,
+            _ => Focus::Deck(*pile),
+        },
+        Focus::Discard => match action {
+            InputAction::NextSlot => Focus::Slot(current_hand),
+            InputAction::PrevRow => Focus::Deck(0),
+            _ => Focus::Discard,
+        },
+        Focus::Slot(slot) => {
+            let row = SLOT_ROWS
+                .iter()
+                .position(|(team, slot_type)| *team == slot.team && *slot_type == slot.slot_type)
+                .unwrap_or(0);
+            let (row, id) = match action {
+                InputAction::PrevRow => (row.saturating_sub(1), slot.id),
+                InputAction::NextRow => ((row + 1).min(SLOT_ROWS.len() - 1), slot.id),
+                InputAction::PrevSlot if slot.id == 0 => {
+                    return Focus::Deck(piles.saturating_sub(1))
+                }
+                InputAction::PrevSlot => (row, slot.id - 1),
+                InputAction::NextSlot => (row, (slot.id + 1).min(CARD_SLOT_COUNT - 1)),
+                _ => (row, slot.id),
+            };
+            Focus::Slot(CardSlot {
+                id,
+                team: SLOT_ROWS[row].0,
+                slot_type: SLOT_ROWS[row].1,
+            })
+        }
+    }
+}
+
+// the events a focus input can turn into
+#[derive(SystemParam)]
+struct FocusPresses<'w> {
+    slot: EventWriter<'w, CardSlotPressed>,
+    deck: EventWriter<'w, CardDeckPressed>,
+    discard: EventWriter<'w, DiscardPressed>,
+    cancel: EventWriter<'w, CancelHeldCard>,
+    end_turn: EventWriter<'w, EndTurnPressed>,
+    undo: EventWriter<'w, UndoPressed>,
+    settings_row: EventWriter<'w, SettingsRowPressed>,
+}
+
+fn move_settings_focus(focus: &Focus, action: InputAction) -> Focus {
+    let row = match focus {
+        Focus::Setting(row) => *row,
+        _ => {
+            return Focus::Setting(0);
+        }
+    };
+    match action {
+        InputAction::NextRow | InputAction::NextSlot => {
+            Focus::Setting((row + 1).min(SETTINGS_ROW_COUNT - 1))
+        }
+        InputAction::PrevRow | InputAction::PrevSlot => Focus::Setting(row.saturating_sub(1)),
+        _ => Focus::Setting(row),
+    }
+}
+
+// while the settings screen is open the navigation actions walk its rows instead of the board
+fn handle_settings_focus_input(action: InputAction, focus: &mut Focus, presses: &mut FocusPresses) {
+    match (action, focus.clone()) {
+        (InputAction::PickUp | InputAction::Place, Focus::Setting(row)) => {
+            presses.settings_row.send(SettingsRowPressed(row));
+        }
+        (
+            InputAction::PickUp
+            | InputAction::Place
+            | InputAction::NextSlot
+            | InputAction::PrevSlot
+            | InputAction::NextRow
+            | InputAction::PrevRow,
+            _,
+        ) => *focus = move_settings_focus(focus, action),
+        _ => {}
+    }
+}
+
+// the screens that take input away from the board while they are up
+#[derive(SystemParam)]
+struct InputOverlays<'w> {
+    settings_screen: Res<'w, SettingsScreen>,
+    pass_device: Res<'w, PassDevice>,
+}
+
+// the board state a focus input is read against
+#[derive(SystemParam)]
+struct FocusBoard<'w, 's> {
+    current_turn_team: Res<'w, State<CurrentTurnTeam>>,
+    game_ui_controller_query: Query<'w, 's, &'static GameController>,
+    custom_cursor_query: Query<'w, 's, &'static CustomCursor>,
+}
+
+fn handle_focus_input(
+    action_inputs: ActionInputs,
+    overlays: InputOverlays,
+    board: FocusBoard,
+    mut focus: ResMut<Focus>,
+    mut match_log: ResMut<MatchLog>,
+    mut presses: FocusPresses,
+) {
+    if overlays.settings_screen.listening.is_some() || overlays.pass_device.waiting_for.is_some() {
+        return;
+    }
+    let team = board.current_turn_team.get().0;
+    let piles = match board.game_ui_controller_query.get_single() {
+        Ok(x) => x.get_next_card_types(team).len(),
+        _ => 1,
+    };
+    let holding_card = matches!(
+        board.custom_cursor_query.get_single(),
+        Ok(CustomCursor::Card { .. })
+    );
+    for action in action_inputs.get_just_pressed_actions() {
+        if overlays.settings_screen.open {
+            handle_settings_focus_input(action, &mut focus, &mut presses);
+            continue;
+        }
+        match action {
+            InputAction::PickUp | InputAction::Place => {
+                // pick up and place usually share a binding, only the one matching the cursor applies
+                if (action == InputAction::Place) != holding_card {
+                    continue;
+                }
+                match focus.clone() {
+                    Focus::Deck(pile) => {
+                        presses
+                            .deck
+                            .send(CardDeckPressed(CardDeckMarker { team, pile }));
+                    }
+                    Focus::Discard => {
+                        presses.discard.send(DiscardPressed);
+                    }
+                    Focus::Slot(slot) => {
+                        presses.slot.send(CardSlotPressed(slot));
+                    }
+                    Focus::None | Focus::Setting(_) => {
+                        *focus = move_focus(&focus, action, team, piles)
+                    }
+                }
+            }
+            InputAction::Draw => {
+                let pile = match *focus {
+                    Focus::Deck(pile) => pile,
+                    _ => 0,
+                };
+                presses
+                    .deck
+                    .send(CardDeckPressed(CardDeckMarker { team, pile }));
+            }
+            InputAction::Cancel => {
+                presses.cancel.send(CancelHeldCard);
+            }
+            InputAction::EndTurn => {
+                presses.end_turn.send(EndTurnPressed);
+            }
+            InputAction::Undo => {
+                presses.undo.send(UndoPressed);
+            }
+            InputAction::OpenLog => match_log.open = !match_log.open,
+            InputAction::NextSlot
+            | InputAction::PrevSlot
+            | InputAction::NextRow
+            | InputAction::PrevRow => *focus = move_focus(&focus, action, team, piles),
+        }
+    }
+}
+
+fn clear_focus_on_mouse_move(mut cursor_evr: EventReader<CursorMoved>, mut focus: ResMut<Focus>) {
+    if cursor_evr.read().count() > 0 && *focus != Focus::None {
+        *focus = Focus::None;
+    }
+}
+
+// everything the focus can land on
+#[derive(SystemParam)]
+struct FocusTargets<'w, 's> {
+    slot_button_query: Query<'w, 's, Entity, With<CardSlotMarker>>,
+    deck_query: Query<'w, 's, (Entity, &'static CardDeckMarker)>,
+    discard_query: Query<'w, 's, Entity, With<DiscardMarker>>,
+    settings_row_query: Query<'w, 's, (Entity, &'static SettingsRow)>,
+    card_slot_query: Query<'w, 's, &'static CardSlot>,
+    children_query: Query<'w, 's, &'static Children>,
+}
+
+impl FocusTargets<'_, '_> {
+    fn get_focused_entity(&self, focus: &Focus, team: Team) -> Option<Entity> {
+        match focus {
+            Focus::None => None,
+            Focus::Setting(row) => self
+                .settings_row_query
+                .iter()
+                .find(|(_, x)| x.0 == *row)
+                .map(|(entity, _)| entity),
+            Focus::Deck(pile) => self
+                .deck_query
+                .iter()
+                .find(|(_, deck)| deck.team == team && deck.pile == *pile)
+                .map(|(entity, _)| entity),
+            Focus::Discard => self.discard_query.iter().next(),
+            Focus::Slot(slot) => self.slot_button_query.iter().find(|entity| {
+                self.children_query
+                    .iter_descendants(*entity)
+                    .next()
+                    .and_then(|x| self.card_slot_query.get(x).ok())
+                    == Some(slot)
+            }),
+        }
+    }
+}
+
+// only the outlines focus draws, gems and status icons keep their own
+type FocusOutline = Or<(
+    With<CardSlotMarker>,
+    With<CardDeckMarker>,
+    With<DiscardMarker>,
+    With<SettingsRow>,
+)>;
+
+fn draw_focus_outline(
+    focus: Res<Focus>,
+    current_turn_team: Res<State<CurrentTurnTeam>>,
+    targets: FocusTargets,
+    mut outline_query: Query<(Entity, &mut Outline, &GlobalTransform), FocusOutline>,
+    mut custom_cursor_query: Query<(&CustomCursor, &mut Style)>,
+) {
+    if !focus.is_changed() && !current_turn_team.is_changed() {
+        return;
+    }
+    let focused = targets.get_focused_entity(&focus, current_turn_team.get().0);
+    for (entity, mut outline, transform) in outline_query.iter_mut() {
+        if Some(entity) != focused {
+            outline.color = Color::NONE;
+            continue;
+        }
+        outline.color = FOCUS_COLOR;
+        // a held card follows the focus instead of the mouse while navigating without one
+        if let Ok((CustomCursor::Card { .. }, mut style)) = custom_cursor_query.get_single_mut() {
+            style.left = Val::Px(transform.translation().x);
+            style.top = Val::Px(transform.translation().y);
+        }
+    }
+}
+
+pub struct FocusPlugin;
+
+impl Plugin for FocusPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Focus>().add_systems(
+            Update,
+            (
+                clear_focus_on_mouse_move,
+                handle_focus_input,
+                draw_focus_outline,
+            )
+                .chain(),
+        );
+    }
+}
@@ -1,108 +1,80 @@
+use crate::ai::AiPlugin;
+use crate::card_preview::CardPreviewPlugin;
 use crate::cards::{
     get_card_back_image, Card, CardAssetPlugin, CardBack, CardBackAssetPlugin, CardBackType,
-    CardType,
 };
+use crate::color_chains::ColorChainPlugin;
 use crate::custom_cursor::{CustomCursor, CustomCursorPlugin};
+use crate::focus::FocusPlugin;
 use crate::game_state::{
-    CardDeckMarker, CardSlot, CardSlotMarker, CardSlotType, CardStats, CurrentTurnTeam,
-    NextTurnCardType, Team, TurnState,
+    ActionApplied, ActionRejected, CancelHeldCard, CardDeckMarker, CardDeckPressed, CardSlot,
+    CardSlotMarker, CardSlotPressed, CardSlotType, CurrentTurnTeam, DiscardMarker, DiscardPressed,
+    DrawBlockedMarker, EndTurnButtonMarker, EndTurnPressed, PlayerActionEvent, StateDeltaEvent,
+    Team, TurnState, UndoButtonMarker, UndoPressed,
 };
 use crate::game_ui_controller::{GameController, GameUiControllerPlugin};
+use crate::hot_seat::HotSeatPlugin;
+use crate::input_map::InputMapPlugin;
+use crate::launch_options::LaunchOptions;
+use crate::match_log::{MatchLog, MatchLogPlugin};
+use crate::mulligan::MulliganPlugin;
+use crate::network::NetworkPlugin;
+use crate::protocol::{encode_ron, PlayerAction, StateDelta};
+use crate::replay::ReplayPlugin;
+use crate::settings_screen::SettingsScreenPlugin;
+use crate::status_effects::StatusEffectPlugin;
+use crate::turn_hud::TurnHudPlugin;
+use crate::turn_timer::{TurnTimer, TurnTimerPlugin};
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use bevy_rand::prelude::WyRand;
 use bevy_rand::resource::GlobalEntropy;
-use num_traits::FromPrimitive;
-use rand::Rng;
+
+const DISABLED_TINT: Color = Color::rgb(0.4, 0.4, 0.4);
 
 pub fn draw_card(
-    mut interaction_query: Query<
-        &Interaction,
-        (Changed<Interaction>, With<Button>, With<CardDeckMarker>),
-    >,
-    mut game_ui_controller_query: Query<&mut GameController>,
-    mut rng: ResMut<GlobalEntropy<WyRand>>,
-    cards: Res<Assets<Card>>,
-    current_card_type_state: Res<State<NextTurnCardType>>,
-    mut card_type_state: ResMut<NextState<NextTurnCardType>>,
-    current_turn_team: Res<State<CurrentTurnTeam>>,
-    mut turn_state: ResMut<NextState<TurnState>>,
-    current_turn_state: Res<State<TurnState>>,
-    mut draw_image_query: Query<&mut UiImage, With<CardDeckMarker>>,
-    card_backs: Res<Assets<CardBack>>,
+    mut deck_pressed: EventReader<CardDeckPressed>,
+    game_ui_controller_query: Query<&GameController>,
+    launch_options: Res<LaunchOptions>,
+    mut player_actions: EventWriter<PlayerActionEvent>,
 ) {
-    if *current_turn_state.get() != TurnState::DrawCards {
-        return;
-    }
-    let mut game_ui_controller = match game_ui_controller_query.get_single_mut() {
+    let game_ui_controller = match game_ui_controller_query.get_single() {
         Ok(x) => x,
         _ => {
+            deck_pressed.clear();
             return;
         }
     };
-    for interaction in &mut interaction_query {
-        match *interaction {
-            Interaction::Pressed => {
-                let card_color_count = rng.gen_range(1..3);
-                let random_card_of_type = game_ui_controller.get_random_card_of_type_with_len(
-                    &mut rng,
-                    &cards,
-                    current_card_type_state.get().0.clone(),
-                    card_color_count,
-                );
-                let random_card_asset = cards.get(random_card_of_type).unwrap();
-                match game_ui_controller
-                    .get_first_open_slot(current_turn_team.0, CardSlotType::Hand)
-                {
-                    Some(x) => {
-                        game_ui_controller.push_card_into_stack(
-                            CardSlot {
-                                id: x,
-                                team: current_turn_team.0,
-                                slot_type: CardSlotType::Hand,
-                            },
-                            random_card_of_type,
-                            CardStats {
-                                hp: random_card_asset.hp,
-                            },
-                        );
-                        game_ui_controller.stack_cards(current_turn_team.0, CardSlotType::Hand);
-                    }
-                    None => {}
-                }
-
-                let new_card_type = CardType::from_i8(rng.gen_range(0..4)).unwrap();
-                card_type_state.set(NextTurnCardType(new_card_type));
-                match draw_image_query.get_single_mut() {
-                    Ok(mut x) => {
-                        x.texture =
-                            get_card_back_image(&card_backs, CardBackType::CardType(new_card_type))
-                    }
-                    _ => {}
-                }
-                turn_state.set(TurnState::PlayCards);
-            }
-            _ => {}
+    let team = game_ui_controller.get_current_team();
+    // presses on the other team's piles do nothing
+    let pile = match deck_pressed.read().find(|x| x.0.team == team) {
+        Some(x) => x.0.pile,
+        None => {
+            return;
         }
+    };
+    deck_pressed.clear();
+    // the deck is disabled until a full hand made room, see update_draw_blocked
+    if game_ui_controller.get_turn_state() != TurnState::DrawCards
+        || !launch_options.controls_team(team)
+        || game_ui_controller.card_stack_full(team, CardSlotType::Hand)
+    {
+        return;
     }
+    player_actions.send(PlayerActionEvent {
+        team,
+        action: PlayerAction::Draw { pile },
+    });
 }
 
 fn play_card(
     mut game_ui_controller_query: Query<&mut GameController>,
     mut custom_cursor_query: Query<&mut CustomCursor>,
-    mut interaction_query: Query<
-        (&Interaction, Entity),
-        (Changed<Interaction>, With<Button>, With<CardSlotMarker>),
-    >,
-    card_slot_query: Query<&CardSlot>,
-    children_query: Query<&Children>,
-    current_turn_state: Res<State<TurnState>>,
-    mut turn_state: ResMut<NextState<TurnState>>,
-    current_turn_team: Res<State<CurrentTurnTeam>>,
-    cards: Res<Assets<Card>>,
+    mut slot_pressed: EventReader<CardSlotPressed>,
+    mut discard_pressed: EventReader<DiscardPressed>,
+    launch_options: Res<LaunchOptions>,
+    mut player_actions: EventWriter<PlayerActionEvent>,
 ) {
-    if *current_turn_state.get() != TurnState::PlayCards {
-        return;
-    }
     let mut game_ui_controller = match game_ui_controller_query.get_single_mut() {
         Ok(x) => x,
         _ => {
@@ -115,213 +87,412 @@ fn play_card(
             return;
         }
     };
+    let team = game_ui_controller.get_current_team();
+    let turn_state = game_ui_controller.get_turn_state();
+    // a full hand has to drop a card on the discard pile before drawing
+    let discarding = turn_state == TurnState::DrawCards
+        && game_ui_controller.card_stack_full(team, CardSlotType::Hand);
+    if !(turn_state == TurnState::PlayCards || discarding) || !launch_options.controls_team(team) {
+        // the turn can end without the player, e.g. from the turn timer
+        return_held_card(&mut game_ui_controller, &mut custom_cursor);
+        slot_pressed.clear();
+        discard_pressed.clear();
+        return;
+    }
     match custom_cursor.clone() {
-        // pick up card and set custom cursor
+        // pick up card and set custom cursor, the board only changes once the card is placed
         CustomCursor::Default => {
-            for (interaction, entity) in &mut interaction_query {
-                let slot = card_slot_query
-                    .get(children_query.iter_descendants(entity).nth(0).unwrap())
-                    .unwrap();
-                if !(slot.team == current_turn_team.get().0 && slot.slot_type == CardSlotType::Hand)
-                {
+            discard_pressed.clear();
+            for CardSlotPressed(slot) in slot_pressed.read() {
+                if !(slot.team == team && slot.slot_type == CardSlotType::Hand) {
                     continue;
                 }
-                match *interaction {
-                    Interaction::Pressed => match game_ui_controller.get_card(&slot) {
-                        Some(card) => {
-                            *custom_cursor = CustomCursor::Card {
-                                card: card.0,
-                                stats: game_ui_controller.get_card(slot).unwrap().1,
-                                original_slot: slot.clone(),
-                            };
-                            game_ui_controller.remove_card(slot.clone());
-                        }
-                        _ => {}
-                    },
-                    _ => {}
+                if let Some((card, stats)) = game_ui_controller.get_card(slot) {
+                    *custom_cursor = CustomCursor::Card {
+                        card,
+                        stats,
+                        original_slot: slot.clone(),
+                    };
+                    game_ui_controller.hide_card(slot.clone());
+                    break;
                 }
             }
         }
-        // place custom cursor down in play and adjust slots
-        CustomCursor::Card {
-            card,
-            stats,
-            original_slot,
-        } => {
-            let held_card = cards.get(card).unwrap();
-            for (interaction, entity) in &mut interaction_query {
-                let slot = card_slot_query
-                    .get(children_query.iter_descendants(entity).nth(0).unwrap())
-                    .unwrap();
-                if !slot.team == current_turn_team.get().0 && slot.slot_type == CardSlotType::Play {
-                    continue;
+        // dropping the card on the team's play row asks the rules to place it, anywhere else returns it
+        CustomCursor::Card { original_slot, .. } => {
+            if discard_pressed.read().count() > 0 {
+                game_ui_controller.show_card(original_slot.clone());
+                *custom_cursor = CustomCursor::Default;
+                if discarding {
+                    player_actions.send(PlayerActionEvent {
+                        team,
+                        action: PlayerAction::Discard {
+                            hand_slot: original_slot.id,
+                        },
+                    });
                 }
-                let mut failed_to_combine_but_is_placing = false;
-                let mut failover_back_to_original = false;
-                match *interaction {
-                    Interaction::Pressed => {
-                        match game_ui_controller.get_card(slot) {
-                            Some(x) => {
-                                let slot_card = cards.get(x.0).unwrap();
-
-                                if cards_can_combine(slot_card, held_card)
-                                    || cards_can_combine(held_card, slot_card)
-                                {
-                                    let mut colors = slot_card.colors.clone();
-                                    colors.extend(held_card.colors.clone());
-                                    match game_ui_controller.get_card_with_colors(
-                                        colors,
-                                        &*cards,
-                                        get_upgraded_card_type(slot_card, held_card),
-                                    ) {
-                                        Some(x) => {
-                                            game_ui_controller.push_card_at(
-                                                slot.clone(),
-                                                x.1,
-                                                CardStats { hp: x.0.hp },
-                                            );
-                                        }
-                                        None => {
-                                            failover_back_to_original = true;
-                                        }
-                                    }
-                                } else {
-                                    failed_to_combine_but_is_placing = true;
-                                }
-                            }
-                            None => {
-                                failed_to_combine_but_is_placing = true;
-                            }
-                        };
-
-                        if failed_to_combine_but_is_placing {
-                            let mut left_slot: Option<&Card> = None;
-                            if slot.id > 0 {
-                                match game_ui_controller.get_card(&CardSlot {
-                                    id: slot.id - 1,
-                                    team: slot.team,
-                                    slot_type: slot.slot_type,
-                                }) {
-                                    Some(x) => {
-                                        left_slot = Some(cards.get(x.0).unwrap());
-                                    }
-                                    None => {}
-                                }
-                            }
-                            if can_place_card(left_slot, held_card) {
-                                game_ui_controller.push_card_into_stack(
-                                    slot.clone(),
-                                    card,
-                                    stats.clone(),
-                                );
-                            } else {
-                                failover_back_to_original = true;
-                            }
-                        } else {
-                            failover_back_to_original = true;
-                        }
-                        if failover_back_to_original {
-                            game_ui_controller.push_card_at(
-                                original_slot.clone(),
-                                card,
-                                stats.clone(),
-                            );
-                        } else {
-                            turn_state.set(TurnState::ApplyMoves);
-                        }
-                        game_ui_controller.stack_cards(slot.team, slot.slot_type);
-                        *custom_cursor = CustomCursor::Default;
-                    }
-                    _ => {}
+                slot_pressed.clear();
+                return;
+            }
+            if let Some(CardSlotPressed(slot)) = slot_pressed.read().next() {
+                game_ui_controller.show_card(original_slot.clone());
+                *custom_cursor = CustomCursor::Default;
+                if turn_state == TurnState::PlayCards
+                    && slot.team == team
+                    && slot.slot_type == CardSlotType::Play
+                {
+                    player_actions.send(PlayerActionEvent {
+                        team,
+                        action: PlayerAction::Place {
+                            hand_slot: original_slot.id,
+                            play_slot: slot.id,
+                        },
+                    });
                 }
             }
+            slot_pressed.clear();
+        }
+    }
+}
+
+fn return_held_card(game_ui_controller: &mut GameController, custom_cursor: &mut CustomCursor) {
+    match custom_cursor.clone() {
+        CustomCursor::Card { original_slot, .. } => {
+            game_ui_controller.show_card(original_slot);
+            *custom_cursor = CustomCursor::Default;
         }
+        CustomCursor::Default => {}
     }
 }
 
-fn cards_can_combine(first_card: &Card, second_card: &Card) -> bool {
-    return vec![CardType::Hero, CardType::Beast].contains(&first_card.card_type)
-        && second_card
-            .colors
-            .iter()
-            .all(|x| !first_card.colors.contains(x))
-        && second_card.card_type == CardType::Equipment;
+fn cancel_held_card(
+    mut cancel_pressed: EventReader<CancelHeldCard>,
+    mut game_ui_controller_query: Query<&mut GameController>,
+    mut custom_cursor_query: Query<&mut CustomCursor>,
+) {
+    if cancel_pressed.read().count() == 0 {
+        return;
+    }
+    if let (Ok(mut x), Ok(mut y)) = (
+        game_ui_controller_query.get_single_mut(),
+        custom_cursor_query.get_single_mut(),
+    ) {
+        return_held_card(&mut x, &mut y)
+    }
 }
 
-fn can_place_card(placed_to_left: Option<&Card>, to_place: &Card) -> bool {
-    match placed_to_left {
-        None => true,
-        Some(x) => {
-            return x.colors.last().unwrap() == to_place.colors.first().unwrap();
+fn end_turn(
+    mut end_turn_pressed: EventReader<EndTurnPressed>,
+    mut game_ui_controller_query: Query<&mut GameController>,
+    mut custom_cursor_query: Query<&mut CustomCursor>,
+    launch_options: Res<LaunchOptions>,
+    mut player_actions: EventWriter<PlayerActionEvent>,
+) {
+    if end_turn_pressed.read().count() == 0 {
+        return;
+    }
+    let mut game_ui_controller = match game_ui_controller_query.get_single_mut() {
+        Ok(x) => x,
+        _ => {
+            return;
         }
+    };
+    let team = game_ui_controller.get_current_team();
+    if game_ui_controller.get_turn_state() != TurnState::PlayCards
+        || !launch_options.controls_team(team)
+    {
+        return;
     }
+    if let Ok(mut x) = custom_cursor_query.get_single_mut() {
+        return_held_card(&mut game_ui_controller, &mut x)
+    }
+    player_actions.send(PlayerActionEvent {
+        team,
+        action: PlayerAction::EndTurn,
+    });
 }
 
-fn get_upgraded_card_type(first_card: &Card, second_card: &Card) -> CardType {
-    if vec![CardType::Hero, CardType::Beast].contains(&first_card.card_type) {
-        return first_card.card_type;
+fn undo_placement(
+    mut undo_pressed: EventReader<UndoPressed>,
+    mut game_ui_controller_query: Query<&mut GameController>,
+    mut custom_cursor_query: Query<&mut CustomCursor>,
+    launch_options: Res<LaunchOptions>,
+    mut player_actions: EventWriter<PlayerActionEvent>,
+) {
+    if undo_pressed.read().count() == 0 {
+        return;
     }
-    if vec![CardType::Hero, CardType::Beast].contains(&second_card.card_type) {
-        return second_card.card_type;
+    let mut game_ui_controller = match game_ui_controller_query.get_single_mut() {
+        Ok(x) => x,
+        _ => {
+            return;
+        }
+    };
+    let team = game_ui_controller.get_current_team();
+    if game_ui_controller.get_turn_state() != TurnState::PlayCards
+        || !launch_options.controls_team(team)
+    {
+        return;
     }
-    panic!("unreachable")
+    if let Ok(mut x) = custom_cursor_query.get_single_mut() {
+        return_held_card(&mut game_ui_controller, &mut x)
+    }
+    player_actions.send(PlayerActionEvent {
+        team,
+        action: PlayerAction::Undo,
+    });
 }
 
-fn apply_moves(
-    current_turn_state: Res<State<TurnState>>,
-    card_slot_query: Query<&CardSlot>,
-    current_turn_team: Res<State<CurrentTurnTeam>>,
+// what became of each action the rules were asked to apply
+#[derive(SystemParam)]
+struct ActionOutcomes<'w> {
+    applied: EventWriter<'w, ActionApplied>,
+    rejected: EventWriter<'w, ActionRejected>,
+}
+
+// the host (or a local game) is the only place actions are checked against the rules
+fn apply_player_actions(
+    mut player_actions: EventReader<PlayerActionEvent>,
     mut game_ui_controller_query: Query<&mut GameController>,
+    mut rng: ResMut<GlobalEntropy<WyRand>>,
     cards: Res<Assets<Card>>,
-    mut team_state: ResMut<NextState<CurrentTurnTeam>>,
-    mut turn_state: ResMut<NextState<TurnState>>,
+    launch_options: Res<LaunchOptions>,
+    turn_timer: Res<TurnTimer>,
+    mut outcomes: ActionOutcomes,
 ) {
+    if launch_options.is_network_client() {
+        player_actions.clear();
+        return;
+    }
     let mut game_ui_controller = match game_ui_controller_query.get_single_mut() {
         Ok(x) => x,
         _ => {
             return;
         }
     };
-    let team_health = game_ui_controller
-        .get_team_health(current_turn_team.get().0)
-        .clone();
-    if *current_turn_state.get() != TurnState::ApplyMoves {
+    for PlayerActionEvent { team, action } in player_actions.read() {
+        let turn = game_ui_controller.get_turn();
+        match game_ui_controller.apply_action(*team, action, &cards, rng.as_mut()) {
+            Ok(_) => {
+                outcomes.applied.send(ActionApplied {
+                    team: *team,
+                    action: action.clone(),
+                    turn,
+                    elapsed_ms: turn_timer.get_elapsed_ms(),
+                });
+            }
+            Err(err) => {
+                warn!("rejected {:?} from {:?}: {}", action, team, err);
+                outcomes.rejected.send(ActionRejected {
+                    team: *team,
+                    reason: err.to_string(),
+                });
+            }
+        }
+    }
+}
+
+fn resolve_moves(
+    mut game_ui_controller_query: Query<&mut GameController>,
+    cards: Res<Assets<Card>>,
+    launch_options: Res<LaunchOptions>,
+    mut rng: ResMut<GlobalEntropy<WyRand>>,
+) {
+    if launch_options.is_network_client() {
         return;
     }
-    for (current_slot, foe_slot) in card_slot_query
-        .iter()
-        .filter(|slot| {
-            slot.team == current_turn_team.get().0 && slot.slot_type == CardSlotType::Play
-        })
-        .zip(card_slot_query.iter().filter(|slot| {
-            slot.team == !current_turn_team.get().0 && slot.slot_type == CardSlotType::Play
-        }))
-    {
-        let foe_card = game_ui_controller
-            .get_card(foe_slot)
-            .map(|x| cards.get(x.0).unwrap());
-        let current_card = game_ui_controller
-            .get_card(current_slot)
-            .map(|x| cards.get(x.0).unwrap());
-        match (current_card, foe_card) {
-            (Some(current), Some(foe)) => {
-                game_ui_controller.damage_card(foe_slot, current.damage.unwrap_or(0));
-                game_ui_controller.damage_card(current_slot, foe.damage.unwrap_or(0));
+    if let Ok(mut x) = game_ui_controller_query.get_single_mut() {
+        x.resolve_moves(&cards, rng.as_mut())
+    }
+}
+
+fn log_rejected_actions(
+    mut rejected: EventReader<ActionRejected>,
+    launch_options: Res<LaunchOptions>,
+    mut match_log: ResMut<MatchLog>,
+) {
+    for ActionRejected { team, reason } in rejected.read() {
+        if launch_options.controls_team(*team) {
+            match_log.push(format!("{:?} can't do that: {}", team, reason));
+        }
+    }
+}
+
+fn publish_state_deltas(
+    mut game_ui_controller_query: Query<&mut GameController>,
+    mut state_deltas: EventWriter<StateDeltaEvent>,
+) {
+    if let Ok(mut x) = game_ui_controller_query.get_single_mut() {
+        for delta in x.take_deltas() {
+            state_deltas.send(StateDeltaEvent(delta));
+        }
+    }
+}
+
+fn forward_controller_log(
+    mut state_deltas: EventReader<StateDeltaEvent>,
+    mut match_log: ResMut<MatchLog>,
+) {
+    for StateDeltaEvent(delta) in state_deltas.read() {
+        match delta {
+            StateDelta::Log(entry) => match_log.push(entry.clone()),
+            _ => debug!("{}", encode_ron(delta)),
+        }
+    }
+}
+
+// the bevy states mirror the controller so the rest of the ui can react to turn changes
+fn sync_turn_states(
+    game_ui_controller_query: Query<&GameController>,
+    current_turn_state: Res<State<TurnState>>,
+    mut turn_state: ResMut<NextState<TurnState>>,
+    current_turn_team: Res<State<CurrentTurnTeam>>,
+    mut turn_team: ResMut<NextState<CurrentTurnTeam>>,
+) {
+    let game_ui_controller = match game_ui_controller_query.get_single() {
+        Ok(x) => x,
+        _ => {
+            return;
+        }
+    };
+    if *current_turn_state.get() != game_ui_controller.get_turn_state() {
+        turn_state.set(game_ui_controller.get_turn_state());
+    }
+    if current_turn_team.get().0 != game_ui_controller.get_current_team() {
+        turn_team.set(CurrentTurnTeam(game_ui_controller.get_current_team()));
+    }
+}
+
+// every team's piles show the backs of the types it draws from next, unused piles are hidden
+fn update_deck_images(
+    game_ui_controller_query: Query<&GameController>,
+    mut deck_query: Query<(&CardDeckMarker, &mut UiImage, &mut Style)>,
+    card_backs: Res<Assets<CardBack>>,
+) {
+    let game_ui_controller = match game_ui_controller_query.get_single() {
+        Ok(x) => x,
+        _ => {
+            return;
+        }
+    };
+    for (deck, mut image, mut style) in deck_query.iter_mut() {
+        let card_types = game_ui_controller.get_next_card_types(deck.team);
+        let display = match card_types.get(deck.pile) {
+            Some(card_type) => {
+                let texture = get_card_back_image(&card_backs, CardBackType::CardType(*card_type));
+                if image.texture != texture {
+                    image.texture = texture;
+                }
+                Display::Flex
             }
-            (None, Some(foe)) => game_ui_controller.set_team_health(
-                current_turn_team.get().0,
-                team_health - foe.damage.unwrap_or(0),
-            ),
-            (Some(current), None) => game_ui_controller.set_team_health(
-                current_turn_team.get().0,
-                team_health - current.damage.unwrap_or(0),
-            ),
-            _ => {}
+            None => Display::None,
+        };
+        let width = Val::Percent(100.0 / card_types.len().max(1) as f32);
+        if style.display != display || style.width != width {
+            style.display = display;
+            style.width = width;
+        }
+    }
+}
+
+// greys out the current team's piles and says why while its full hand blocks the draw
+fn update_draw_blocked(
+    game_ui_controller_query: Query<&GameController>,
+    launch_options: Res<LaunchOptions>,
+    mut deck_query: Query<(&CardDeckMarker, &mut BackgroundColor)>,
+    mut hint_query: Query<&mut Text, With<DrawBlockedMarker>>,
+) {
+    let game_ui_controller = match game_ui_controller_query.get_single() {
+        Ok(x) => x,
+        _ => {
+            return;
+        }
+    };
+    let team = game_ui_controller.get_current_team();
+    let blocked = game_ui_controller.get_turn_state() == TurnState::DrawCards
+        && game_ui_controller.card_stack_full(team, CardSlotType::Hand);
+    for (deck, mut background) in deck_query.iter_mut() {
+        let tint = match blocked && deck.team == team {
+            true => DISABLED_TINT,
+            false => Color::WHITE,
+        };
+        if background.0 != tint {
+            background.0 = tint;
+        }
+    }
+    let hint = match blocked && launch_options.controls_team(team) {
+        true => "Hand full, drop a card on the discard pile to draw",
+        false => "",
+    };
+    for mut text in hint_query.iter_mut() {
+        if text.sections[0].value != hint {
+            text.sections[0].value = hint.to_string();
         }
     }
+}
+
+type ButtonChangedFilter<T> = (Changed<Interaction>, With<Button>, With<T>);
+
+// the board buttons a pointer can press
+#[derive(SystemParam)]
+struct PointerButtons<'w, 's> {
+    slot_query: Query<'w, 's, (&'static Interaction, Entity), ButtonChangedFilter<CardSlotMarker>>,
+    deck_query: Query<
+        'w,
+        's,
+        (&'static Interaction, &'static CardDeckMarker),
+        ButtonChangedFilter<CardDeckMarker>,
+    >,
+    discard_query: Query<'w, 's, &'static Interaction, ButtonChangedFilter<DiscardMarker>>,
+    undo_query: Query<'w, 's, &'static Interaction, ButtonChangedFilter<UndoButtonMarker>>,
+    end_turn_query: Query<'w, 's, &'static Interaction, ButtonChangedFilter<EndTurnButtonMarker>>,
+}
 
-    turn_state.set(TurnState::DrawCards);
-    team_state.set(CurrentTurnTeam(!current_turn_team.get().0));
+// the events a pointer press can turn into
+#[derive(SystemParam)]
+struct PointerPresses<'w> {
+    slot: EventWriter<'w, CardSlotPressed>,
+    deck: EventWriter<'w, CardDeckPressed>,
+    discard: EventWriter<'w, DiscardPressed>,
+    undo: EventWriter<'w, UndoPressed>,
+    end_turn: EventWriter<'w, EndTurnPressed>,
+}
+
+fn send_pointer_presses(
+    buttons: PointerButtons,
+    card_slot_query: Query<&CardSlot>,
+    children_query: Query<&Children>,
+    mut presses: PointerPresses,
+) {
+    for (interaction, entity) in buttons.slot_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let slot = card_slot_query
+            .get(children_query.iter_descendants(entity).next().unwrap())
+            .unwrap();
+        presses.slot.send(CardSlotPressed(slot.clone()));
+    }
+    for (interaction, deck) in buttons.deck_query.iter() {
+        if *interaction == Interaction::Pressed {
+            presses.deck.send(CardDeckPressed(*deck));
+        }
+    }
+    for interaction in buttons.discard_query.iter() {
+        if *interaction == Interaction::Pressed {
+            presses.discard.send(DiscardPressed);
+        }
+    }
+    for interaction in buttons.undo_query.iter() {
+        if *interaction == Interaction::Pressed {
+            presses.undo.send(UndoPressed);
+        }
+    }
+    for interaction in buttons.end_turn_query.iter() {
+        if *interaction == Interaction::Pressed {
+            presses.end_turn.send(EndTurnPressed);
+        }
+    }
 }
 
 pub struct GameUIPlugin;
@@ -333,12 +504,53 @@ impl Plugin for GameUIPlugin {
             .register_type::<Team>()
             .register_type::<CardSlot>()
             .init_state::<TurnState>()
-            .init_state::<NextTurnCardType>()
             .init_state::<CurrentTurnTeam>()
+            .add_event::<CardSlotPressed>()
+            .add_event::<CardDeckPressed>()
+            .add_event::<CancelHeldCard>()
+            .add_event::<DiscardPressed>()
+            .add_event::<EndTurnPressed>()
+            .add_event::<UndoPressed>()
+            .add_event::<PlayerActionEvent>()
+            .add_event::<ActionApplied>()
+            .add_event::<ActionRejected>()
+            .add_event::<StateDeltaEvent>()
             .add_plugins(CustomCursorPlugin)
+            .add_plugins(CardPreviewPlugin)
             .add_plugins(CardAssetPlugin)
             .add_plugins(CardBackAssetPlugin)
             .add_plugins(GameUiControllerPlugin)
-            .add_systems(Update, (draw_card, play_card, apply_moves));
+            .add_plugins(InputMapPlugin)
+            .add_plugins(MatchLogPlugin)
+            .add_plugins(SettingsScreenPlugin)
+            .add_plugins(FocusPlugin)
+            .add_plugins(HotSeatPlugin)
+            .add_plugins(NetworkPlugin)
+            .add_plugins(ReplayPlugin)
+            .add_plugins(TurnTimerPlugin)
+            .add_plugins(TurnHudPlugin)
+            .add_plugins(MulliganPlugin)
+            .add_plugins(AiPlugin)
+            .add_plugins(ColorChainPlugin)
+            .add_plugins(StatusEffectPlugin)
+            .add_systems(
+                Update,
+                (
+                    send_pointer_presses,
+                    draw_card,
+                    play_card,
+                    cancel_held_card,
+                    end_turn,
+                    undo_placement,
+                    apply_player_actions,
+                    resolve_moves,
+                    log_rejected_actions,
+                    publish_state_deltas,
+                    forward_controller_log,
+                    sync_turn_states,
+                )
+                    .chain(),
+            )
+            .add_systems(Update, (update_deck_images, update_draw_blocked));
     }
 }
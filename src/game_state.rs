@@ -1,9 +1,25 @@
 use std::ops::Not;
 
-use crate::cards::CardType;
+use crate::cards::Card;
+use crate::protocol::{PlayerAction, StateDelta};
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Reflect, States, Default, Debug, Hash)]
+#[derive(
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Reflect,
+    States,
+    Default,
+    Debug,
+    Hash,
+    Serialize,
+    Deserialize,
+)]
 #[repr(u32)]
 pub enum Team {
     #[default]
@@ -11,6 +27,15 @@ pub enum Team {
     Blue,
 }
 
+impl Team {
+    pub fn ui_color(&self) -> Color {
+        match self {
+            Team::Red => Color::rgb(0.86, 0.2, 0.2),
+            Team::Blue => Color::rgb(0.22, 0.4, 0.9),
+        }
+    }
+}
+
 impl Not for Team {
     type Output = Self;
     fn not(self) -> Self::Output {
@@ -24,30 +49,141 @@ impl Not for Team {
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Reflect, States, Default, Debug, Hash)]
 pub struct CurrentTurnTeam(pub Team);
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Hash, States, Reflect)]
-pub struct NextTurnCardType(pub CardType);
-
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Reflect, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Reflect, Debug, Serialize, Deserialize)]
 #[repr(u32)]
 pub enum CardSlotType {
     Hand,
     Play,
 }
 
-#[derive(Component, Clone, PartialEq, Eq, PartialOrd, Ord, Reflect, Debug)]
+#[derive(
+    Component, Clone, PartialEq, Eq, PartialOrd, Ord, Reflect, Debug, Serialize, Deserialize,
+)]
 pub struct CardSlot {
     pub id: usize,
     pub team: Team,
     pub slot_type: CardSlotType,
 }
 
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Reflect, Debug)]
+#[derive(
+    Clone, Default, PartialEq, Eq, PartialOrd, Ord, Reflect, Debug, Serialize, Deserialize,
+)]
 pub struct CardStats {
     pub hp: Option<u32>,
+    // what healing can bring hp back up to, missing from stats saved before it was tracked
+    #[serde(default)]
+    pub max_hp: Option<u32>,
+    // placed this turn, its on play triggers fire once the turn is ended
+    #[serde(default)]
+    pub just_played: bool,
+    // names of the equipment attached to the card, they go with it when it is destroyed
+    #[serde(default)]
+    pub equipment: Vec<String>,
+    #[serde(default)]
+    pub buffs: Vec<Buff>,
+    #[serde(default)]
+    pub statuses: Vec<Status>,
 }
 
-#[derive(Component, Clone, PartialEq, Eq, PartialOrd, Ord, Reflect)]
-pub struct CardDeckMarker;
+impl CardStats {
+    // a card fresh from the deck
+    pub fn new(card: &Card) -> Self {
+        CardStats {
+            hp: card.hp,
+            max_hp: card.hp,
+            ..default()
+        }
+    }
+
+    pub fn get_max_hp(&self) -> Option<u32> {
+        self.max_hp.or(self.hp)
+    }
+
+    // the damage the buffs add on top of the card and its equipment
+    pub fn get_buff_damage(&self) -> u32 {
+        self.buffs.iter().map(|x| x.damage).sum()
+    }
+
+    fn get_status_total(&self, amount: impl Fn(&StatusEffect) -> Option<u32>) -> u32 {
+        self.statuses.iter().filter_map(|x| amount(&x.effect)).sum()
+    }
+
+    // hp lost at the end of every turn
+    pub fn get_hp_loss(&self) -> u32 {
+        self.get_status_total(|x| match x {
+            StatusEffect::Poisoned(amount) | StatusEffect::Burning(amount) => Some(*amount),
+            _ => None,
+        })
+    }
+
+    // taken off every strike against the card
+    pub fn get_shield(&self) -> u32 {
+        self.get_status_total(|x| match x {
+            StatusEffect::Shielded(amount) => Some(*amount),
+            _ => None,
+        })
+    }
+
+    // taken off the damage the card deals
+    pub fn get_weakness(&self) -> u32 {
+        self.get_status_total(|x| match x {
+            StatusEffect::Weakened(amount) => Some(*amount),
+            _ => None,
+        })
+    }
+
+    pub fn has_status(&self, effect: StatusEffect) -> bool {
+        self.statuses.iter().any(|x| x.effect == effect)
+    }
+
+    pub fn can_strike(&self) -> bool {
+        !self.has_status(StatusEffect::Stunned) && !self.has_status(StatusEffect::Frozen)
+    }
+}
+
+// extra damage and max hp, counted down at the end of every turn and gone at 0
+// a buff without turns lasts until the card dies
+#[derive(
+    Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Reflect, Debug, Serialize, Deserialize,
+)]
+pub struct Buff {
+    pub damage: u32,
+    pub hp: u32,
+    pub turns: Option<u32>,
+}
+
+#[derive(
+    Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Reflect, Debug, Serialize, Deserialize,
+)]
+pub enum StatusEffect {
+    // loses the hp at the end of every turn, from being damaged by a Poison card
+    Poisoned(u32),
+    // the same, but it burns out
+    Burning(u32),
+    // does not strike
+    Stunned,
+    // does not strike and cannot be healed
+    Frozen,
+    Shielded(u32),
+    Weakened(u32),
+}
+
+// turns counts the owner's turns the status lasts through, each start of one counts it down and
+// it wears off at the first start after reaching 0, a status without turns lasts until the card dies
+#[derive(
+    Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Reflect, Debug, Serialize, Deserialize,
+)]
+pub struct Status {
+    pub effect: StatusEffect,
+    pub turns: Option<u32>,
+}
+
+// each team has its own draw piles, the second pile is only shown when the rules offer a choice
+#[derive(Component, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Reflect, Debug)]
+pub struct CardDeckMarker {
+    pub team: Team,
+    pub pile: usize,
+}
 
 #[derive(Component, Clone, PartialEq, Eq, PartialOrd, Ord, Reflect)]
 pub struct DiscardMarker;
@@ -61,10 +197,115 @@ pub struct BlueHealthMarker;
 #[derive(Component, Clone, PartialEq, Eq, PartialOrd, Ord, Reflect)]
 pub struct CardSlotMarker;
 
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, States, Reflect)]
+#[derive(Component, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Reflect)]
+pub enum CardColorPipMarker {
+    First,
+    Last,
+}
+
+// the text nodes a placed card writes its text and stats into
+#[derive(Component, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Reflect)]
+pub enum CardTextMarker {
+    Text,
+    Hp,
+    Damage,
+}
+
+#[derive(Component, Clone, PartialEq, Eq, PartialOrd, Ord, Reflect)]
+pub struct CardTypeIconMarker;
+
+#[derive(Component, Clone, PartialEq, Eq, PartialOrd, Ord, Reflect)]
+pub struct CardRarityGemMarker;
+
+#[derive(Component, Clone, PartialEq, Eq, PartialOrd, Ord, Reflect)]
+pub struct CardEquipmentTextMarker;
+
+// the gap between a play slot and the one to its right
+#[derive(Component, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Reflect, Debug)]
+pub struct ChainLinkMarker {
+    pub team: Team,
+    pub id: usize,
+}
+
+// shows the status at index in the card's list, hidden when it has fewer
+#[derive(Component, Clone, PartialEq, Eq, PartialOrd, Ord, Reflect, Debug)]
+pub struct StatusIconMarker {
+    pub slot: CardSlot,
+    pub index: usize,
+}
+
+#[derive(Component, Clone, PartialEq, Eq, PartialOrd, Ord, Reflect)]
+pub struct EnergyMarker;
+
+#[derive(Component, Clone, PartialEq, Eq, PartialOrd, Ord, Reflect)]
+pub struct UndoButtonMarker;
+
+#[derive(Component, Clone, PartialEq, Eq, PartialOrd, Ord, Reflect)]
+pub struct EndTurnButtonMarker;
+
+#[derive(Component, Clone, PartialEq, Eq, PartialOrd, Ord, Reflect)]
+pub struct DrawBlockedMarker;
+
+#[derive(Event, Clone, PartialEq, Eq, Debug)]
+pub struct CardSlotPressed(pub CardSlot);
+
+#[derive(Event, Clone, PartialEq, Eq, Debug)]
+pub struct CardDeckPressed(pub CardDeckMarker);
+
+#[derive(Event, Clone, PartialEq, Eq, Debug)]
+pub struct CancelHeldCard;
+
+#[derive(Event, Clone, PartialEq, Eq, Debug)]
+pub struct DiscardPressed;
+
+#[derive(Event, Clone, PartialEq, Eq, Debug)]
+pub struct EndTurnPressed;
+
+#[derive(Event, Clone, PartialEq, Eq, Debug)]
+pub struct UndoPressed;
+
+#[derive(Event, Clone, PartialEq, Eq, Debug)]
+pub struct PlayerActionEvent {
+    pub team: Team,
+    pub action: PlayerAction,
+}
+
+#[derive(Event, Clone, PartialEq, Eq, Debug)]
+pub struct StateDeltaEvent(pub StateDelta);
+
+#[derive(Event, Clone, PartialEq, Eq, Debug)]
+pub struct ActionApplied {
+    pub team: Team,
+    pub action: PlayerAction,
+    pub turn: u32,
+    pub elapsed_ms: u32,
+}
+
+#[derive(Event, Clone, PartialEq, Eq, Debug)]
+pub struct ActionRejected {
+    pub team: Team,
+    pub reason: String,
+}
+
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Hash, States, Reflect, Serialize, Deserialize,
+)]
 pub enum TurnState {
+    // both teams may redraw part of their opening hand once before the first draw
+    Mulligan,
     #[default]
     DrawCards,
     PlayCards,
     ApplyMoves,
 }
+
+impl TurnState {
+    pub fn get_phase_name(&self) -> &'static str {
+        match self {
+            TurnState::Mulligan => "Mulligan",
+            TurnState::DrawCards => "Draw",
+            TurnState::PlayCards => "Play",
+            TurnState::ApplyMoves => "Resolve",
+        }
+    }
+}
@@ -1,15 +1,25 @@
 use crate::assets::LoadState;
-use crate::cards::{Card, CardColor, CardType};
+use crate::card_pool::{CardPool, CardPoolError};
+use crate::cards::{get_card_back_image, Card, CardBack, CardBackType, CardColor, CardType};
 use crate::constants::CARD_SLOT_COUNT;
+use crate::equipment::EquippedCard;
 use crate::game_state::{
-    BlueHealthMarker, CardSlot, CardSlotType, CardStats, RedHealthMarker, Team,
+    BlueHealthMarker, CardColorPipMarker, CardEquipmentTextMarker, CardRarityGemMarker, CardSlot,
+    CardSlotType, CardStats, CardTextMarker, CardTypeIconMarker, CurrentTurnTeam, EnergyMarker,
+    RedHealthMarker, Team, TurnState,
 };
+use crate::hot_seat::is_face_down;
+use crate::launch_options::{LaunchOptions, NetworkRole};
+use crate::protocol::{Energy, PlacedCard, StateDelta};
+use crate::replay::ReplayViewer;
+use crate::rules::MatchRules;
 use crate::spawn_ui::spawn_game_ui;
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use bevy_rand::prelude::WyRand;
 use bevy_rand::resource::GlobalEntropy;
 use rand::Rng;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 
 #[derive(Clone, Debug)]
 enum ModifyCardAction {
@@ -23,18 +33,67 @@ enum ModifyCardAction {
     },
 }
 
-#[derive(Component)]
+#[derive(Clone, Debug)]
+struct UndoPoint {
+    cards: BTreeMap<CardSlot, Option<(AssetId<Card>, CardStats)>>,
+    energy: Option<Energy>,
+}
+
+#[derive(Component, Clone)]
 pub struct GameController {
     team_health: BTreeMap<Team, u32>,
     current_cards: BTreeMap<CardSlot, Option<(AssetId<Card>, CardStats)>>,
-    valid_new_cards: Vec<AssetId<Card>>,
+    undo_points: Vec<UndoPoint>,
+    card_pool: CardPool,
     card_ids: Vec<AssetId<Card>>,
+    card_names: HashMap<AssetId<Card>, String>,
+    // the reverse of card_names, for cards that are sent or stored by name
+    card_ids_by_name: HashMap<String, AssetId<Card>>,
     card_modifications: Vec<ModifyCardAction>,
+    destroyed_cards: Vec<(CardSlot, AssetId<Card>, CardStats)>,
+    deltas: Vec<StateDelta>,
     team_health_updated: bool,
+    rules: MatchRules,
+    turn: u32,
+    energy: Option<Energy>,
+    current_team: Team,
+    turn_state: TurnState,
+    next_card_types: BTreeMap<Team, Vec<CardType>>,
 }
 
 impl GameController {
-    pub fn new(cards: &Res<Assets<Card>>, rng: &mut ResMut<GlobalEntropy<WyRand>>) -> Self {
+    pub fn new(cards: &Assets<Card>, rules: &MatchRules, rng: &mut impl Rng) -> Self {
+        let mut gc = GameController::empty(cards, rules);
+        for _ in 0..rules.opening_hand_size {
+            for team in [Team::Blue, Team::Red].iter() {
+                let card = match gc.get_random_card(rng) {
+                    Ok(x) => x,
+                    Err(err) => {
+                        gc.log(format!("{:?} got no opening card, {}", team, err));
+                        continue;
+                    }
+                };
+                gc.push_card_into_stack(
+                    CardSlot {
+                        id: 0,
+                        team: *team,
+                        slot_type: CardSlotType::Hand,
+                    },
+                    card,
+                    CardStats::new(cards.get(card).unwrap()),
+                );
+            }
+        }
+        for team in [Team::Blue, Team::Red] {
+            gc.roll_next_card_types(team, rng);
+        }
+        gc.set_turn(Team::default(), TurnState::Mulligan);
+        gc.refill_energy();
+        gc
+    }
+
+    // a board with no cards dealt, filled in later by the host when joining a networked match
+    pub fn empty(cards: &Assets<Card>, rules: &MatchRules) -> Self {
         let mut card_names: BTreeMap<CardSlot, Option<(AssetId<Card>, CardStats)>> =
             BTreeMap::new();
         for team in [Team::Blue, Team::Red] {
@@ -51,37 +110,40 @@ impl GameController {
                 }
             }
         }
-        let valid_new_cards = cards
-            .iter()
-            .filter(|(_id, card)| -> bool { card.colors.len() < 3 })
-            .map(|x| -> AssetId<Card> { x.0 })
-            .collect();
-        let mut gc = GameController {
-            team_health: BTreeMap::from_iter([(Team::Red, 100), (Team::Blue, 100)]),
+        // asset iteration order differs between runs, sorting keeps a seeded match reproducible
+        let mut sorted_cards: Vec<(AssetId<Card>, &Card)> = cards.iter().collect();
+        sorted_cards.sort_by(|a, b| a.1.name.cmp(&b.1.name));
+        GameController {
+            team_health: BTreeMap::from_iter([
+                (Team::Red, rules.starting_health),
+                (Team::Blue, rules.starting_health),
+            ]),
             current_cards: card_names,
-            valid_new_cards,
-            card_ids: cards.iter().map(|(id, _card)| id).collect(),
+            undo_points: vec![],
+            card_pool: CardPool::new(&sorted_cards, &rules.rarity_weights),
+            card_ids: sorted_cards.iter().map(|(id, _card)| *id).collect(),
+            card_names: cards
+                .iter()
+                .map(|(id, card)| (id, card.name.clone()))
+                .collect(),
+            card_ids_by_name: cards
+                .iter()
+                .map(|(id, card)| (card.name.clone(), id))
+                .collect(),
             card_modifications: vec![],
+            destroyed_cards: vec![],
+            deltas: vec![],
             team_health_updated: false,
-        };
-
-        for _ in 0..4 {
-            for team in vec![Team::Blue, Team::Red].iter() {
-                let card = gc.get_random_card(rng);
-                gc.push_card_into_stack(
-                    CardSlot {
-                        id: 0,
-                        team: team.clone(),
-                        slot_type: CardSlotType::Hand,
-                    },
-                    card,
-                    CardStats {
-                        hp: cards.get(card).unwrap().hp,
-                    },
-                );
-            }
+            rules: rules.clone(),
+            turn: 1,
+            energy: None,
+            current_team: Team::default(),
+            turn_state: TurnState::default(),
+            next_card_types: BTreeMap::from_iter([
+                (Team::Red, vec![CardType::default()]),
+                (Team::Blue, vec![CardType::default()]),
+            ]),
         }
-        gc
     }
 
     pub fn get_team_health(&self, team: Team) -> u32 {
@@ -89,10 +151,111 @@ impl GameController {
     }
 
     pub fn set_team_health(&mut self, team: Team, health: u32) {
+        if self.team_health.get(&team) != Some(&health) {
+            self.deltas
+                .push(StateDelta::TeamHealthChanged { team, health });
+        }
         self.team_health_updated = true;
         self.team_health.insert(team, health);
     }
 
+    pub fn get_rules(&self) -> &MatchRules {
+        &self.rules
+    }
+
+    pub fn set_rules(&mut self, rules: MatchRules) {
+        self.rules = rules;
+    }
+
+    // counts the turns of a single team, every team's turn advances the turn counter
+    pub fn get_round(&self) -> u32 {
+        self.turn.div_ceil(2)
+    }
+
+    // counts every team's turn, starting at 1
+    pub fn get_turn(&self) -> u32 {
+        self.turn
+    }
+
+    pub fn set_turn_number(&mut self, turn: u32) {
+        if self.turn != turn {
+            self.deltas.push(StateDelta::TurnNumberChanged(turn));
+        }
+        self.turn = turn;
+    }
+
+    pub fn advance_turn(&mut self) {
+        self.set_turn_number(self.turn + 1);
+    }
+
+    pub fn get_energy(&self) -> Option<Energy> {
+        self.energy.clone()
+    }
+
+    pub fn set_energy(&mut self, energy: Option<Energy>) {
+        if self.energy != energy {
+            self.deltas.push(StateDelta::EnergyChanged(energy.clone()));
+        }
+        self.energy = energy;
+    }
+
+    pub fn get_current_team(&self) -> Team {
+        self.current_team
+    }
+
+    pub fn get_turn_state(&self) -> TurnState {
+        self.turn_state
+    }
+
+    pub fn set_turn(&mut self, current_team: Team, turn_state: TurnState) {
+        if (current_team, turn_state) != (self.current_team, self.turn_state) {
+            self.deltas.push(StateDelta::TurnChanged {
+                current_team,
+                turn_state,
+            });
+        }
+        self.current_team = current_team;
+        self.turn_state = turn_state;
+    }
+
+    // the card types the team can draw from on its next turn, one pile per entry
+    pub fn get_next_card_types(&self, team: Team) -> &[CardType] {
+        &self.next_card_types[&team]
+    }
+
+    pub fn set_next_card_types(&mut self, team: Team, card_types: Vec<CardType>) {
+        if self.next_card_types.get(&team) != Some(&card_types) {
+            self.deltas.push(StateDelta::NextDrawChanged {
+                team,
+                card_types: card_types.clone(),
+            });
+        }
+        self.next_card_types.insert(team, card_types);
+    }
+
+    pub fn log(&mut self, entry: String) {
+        self.deltas.push(StateDelta::Log(entry));
+    }
+
+    pub fn report_attack(&mut self, slot: CardSlot, card: String, damage: u32) {
+        self.deltas
+            .push(StateDelta::Attacked { slot, card, damage });
+    }
+
+    // a headless match has no board to draw, so the queued ui changes are dropped
+    pub fn clear_card_modifications(&mut self) {
+        self.card_modifications.clear();
+    }
+
+    // model changes since the last call, shared by the match log, networking and replays
+    pub fn take_deltas(&mut self) -> Vec<StateDelta> {
+        std::mem::take(&mut self.deltas)
+    }
+
+    pub fn find_card(&self, name: &str) -> Option<AssetId<Card>> {
+        self.card_ids_by_name.get(name).copied()
+    }
+
     pub fn card_stack_full(&self, team: Team, slot_type: CardSlotType) -> bool {
         for slot in (0..CARD_SLOT_COUNT).into_iter().map(|id| CardSlot {
             team,
@@ -106,6 +269,46 @@ impl GameController {
         return true;
     }
 
+    // snapshots of the board taken before each provisional placement of the turn
+    pub fn save_undo_point(&mut self) {
+        self.undo_points.push(UndoPoint {
+            cards: self.current_cards.clone(),
+            energy: self.energy.clone(),
+        });
+    }
+
+    pub fn discard_undo_point(&mut self) {
+        self.undo_points.pop();
+    }
+
+    pub fn clear_undo_points(&mut self) {
+        self.undo_points.clear();
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_points.is_empty()
+    }
+
+    pub fn undo(&mut self) -> bool {
+        let snapshot = match self.undo_points.pop() {
+            Some(x) => x,
+            None => {
+                return false;
+            }
+        };
+        self.set_energy(snapshot.energy);
+        for (slot, card) in snapshot.cards {
+            if self.current_cards.get(&slot) == Some(&card) {
+                continue;
+            }
+            match card {
+                Some((card, stats)) => self.push_card_at(slot, card, stats),
+                None => self.remove_card(slot),
+            }
+        }
+        true
+    }
+
     pub fn get_card(&self, slot: &CardSlot) -> Option<(AssetId<Card>, CardStats)> {
         self.current_cards.get(slot).map(|x| x.clone())?
     }
@@ -121,20 +324,77 @@ impl GameController {
     }
 
     pub fn push_card_at(&mut self, slot: CardSlot, card: AssetId<Card>, stats: CardStats) {
-        self.card_modifications.push(ModifyCardAction::Push {
+        let modification = ModifyCardAction::Push {
             slot: slot.clone(),
             card,
             stats: stats.clone(),
+        };
+        self.card_modifications.push(modification);
+        self.deltas.push(StateDelta::SlotChanged {
+            slot: slot.clone(),
+            card: Some(PlacedCard {
+                name: self.card_names[&card].clone(),
+                stats: stats.clone(),
+            }),
         });
         self.current_cards.insert(slot, Some((card, stats)));
     }
 
-    pub fn remove_card(&mut self, slot: CardSlot) {
+    // hides a card in the ui without touching the board, used while it is held by the cursor
+    pub fn hide_card(&mut self, slot: CardSlot) {
         self.card_modifications
-            .push(ModifyCardAction::Remove { slot: slot.clone() });
+            .push(ModifyCardAction::Remove { slot });
+    }
+
+    pub fn show_card(&mut self, slot: CardSlot) {
+        match self.get_card(&slot) {
+            Some((card, stats)) => {
+                self.card_modifications
+                    .push(ModifyCardAction::Push { slot, card, stats })
+            }
+            None => self
+                .card_modifications
+                .push(ModifyCardAction::Remove { slot }),
+        }
+    }
+
+    // queues every slot for redrawing, used after the whole board was replaced
+    pub fn refresh_board(&mut self) {
+        for slot in self
+            .current_cards
+            .keys()
+            .cloned()
+            .collect::<Vec<CardSlot>>()
+        {
+            self.show_card(slot);
+        }
+        self.team_health_updated = true;
+    }
+
+    pub fn refresh_cards(&mut self, team: Team, slot_type: CardSlotType) {
+        for (slot, card) in self._clone_iter_current(team, slot_type) {
+            if let Some((card, stats)) = card {
+                self.card_modifications
+                    .push(ModifyCardAction::Push { slot, card, stats })
+            }
+        }
+    }
+
+    pub fn remove_card(&mut self, slot: CardSlot) {
+        let modification = ModifyCardAction::Remove { slot: slot.clone() };
+        self.card_modifications.push(modification);
+        self.deltas.push(StateDelta::SlotChanged {
+            slot: slot.clone(),
+            card: None,
+        });
         self.current_cards.insert(slot, None);
     }
 
+    // the cards damage destroyed since the last call, in the order they fell
+    pub fn take_destroyed_cards(&mut self) -> Vec<(CardSlot, AssetId<Card>, CardStats)> {
+        std::mem::take(&mut self.destroyed_cards)
+    }
+
     pub fn damage_card(&mut self, slot: &CardSlot, damage: u32) {
         let mut slots_to_take = None;
         let mut card_to_push = None;
@@ -155,6 +415,7 @@ impl GameController {
             Some(card) => {
                 let stats = CardStats {
                     hp: card.1.hp.map(|f| f.saturating_sub(damage)),
+                    ..card.1
                 };
                 self.push_card_at(slot.clone(), card.0, stats);
             }
@@ -163,33 +424,30 @@ impl GameController {
 
         match slots_to_take {
             Some(x) => {
+                let (card, stats) = self.get_card(&x).unwrap();
+                self.destroyed_cards.push((x.clone(), card, stats));
                 self.remove_card(x.clone());
             }
             None => {}
         }
     }
 
-    pub fn get_random_card(&self, rng: &mut ResMut<GlobalEntropy<WyRand>>) -> AssetId<Card> {
-        if self.valid_new_cards.len() == 0 {
-            panic!("Card assets failed to load, quitting")
-        }
-        return self.valid_new_cards[rng.gen_range(0usize..self.valid_new_cards.len() - 1)];
+    pub fn get_random_card(&self, rng: &mut impl Rng) -> Result<AssetId<Card>, CardPoolError> {
+        self.card_pool.sample(rng)
     }
 
     pub fn get_random_card_of_type_with_len(
         &self,
-        rng: &mut ResMut<GlobalEntropy<WyRand>>,
-        cards: &Res<Assets<Card>>,
+        rng: &mut impl Rng,
         card_type: CardType,
         color_len: usize,
-    ) -> AssetId<Card> {
-        loop {
-            let card_id = self.get_random_card(rng);
-            let card = cards.get(card_id).unwrap();
-            if card.card_type == card_type && card.colors.len() == color_len {
-                return card_id;
-            }
-        }
+    ) -> Result<AssetId<Card>, CardPoolError> {
+        self.card_pool
+            .sample_of_type_with_len(rng, card_type, color_len)
+    }
+
+    pub fn get_drawable_color_counts(&self, card_type: CardType) -> Vec<usize> {
+        self.card_pool.get_color_counts(card_type)
     }
 
     pub fn get_card_with_colors<'a>(
@@ -276,28 +534,94 @@ impl GameController {
     }
 }
 
-fn push_card(
-    query: &mut Query<(&CardSlot, &mut UiImage, &mut Visibility, Entity)>,
-    child_query: &Query<&mut Children>,
-    text_query: &mut Query<&mut Text>,
-    card: &Card,
-    stats: &CardStats,
-    slot: CardSlot,
-) {
+type TypeIconFilter = (With<CardTypeIconMarker>, Without<CardSlot>);
+type RarityGemFilter = (With<CardRarityGemMarker>, Without<CardColorPipMarker>);
+type EquipmentTextFilter = (With<CardEquipmentTextMarker>, Without<CardTextMarker>);
+
+// the card slot nodes a pushed card is drawn into
+#[derive(SystemParam)]
+struct CardSlotUi<'w, 's> {
+    query: Query<
+        'w,
+        's,
+        (
+            &'static CardSlot,
+            &'static mut UiImage,
+            &'static mut Visibility,
+            Entity,
+        ),
+    >,
+    child_query: Query<'w, 's, &'static Children>,
+    text_query: Query<'w, 's, (&'static CardTextMarker, &'static mut Text)>,
+    pip_query: Query<'w, 's, (&'static CardColorPipMarker, &'static mut BackgroundColor)>,
+    type_icon_query: Query<'w, 's, &'static mut UiImage, TypeIconFilter>,
+    gem_query: Query<'w, 's, &'static mut BackgroundColor, RarityGemFilter>,
+    equipment_text_query: Query<'w, 's, &'static mut Text, EquipmentTextFilter>,
+    overlay_query: Query<'w, 's, &'static mut Visibility, Without<CardSlot>>,
+    card_backs: Res<'w, Assets<CardBack>>,
+}
+
+fn push_card(ui: &mut CardSlotUi, equipped: &EquippedCard, slot: CardSlot, face_down: bool) {
+    let (card, stats) = (equipped.card, &equipped.stats);
+    let CardSlotUi {
+        query,
+        child_query,
+        text_query,
+        pip_query,
+        type_icon_query,
+        gem_query,
+        equipment_text_query,
+        overlay_query,
+        card_backs,
+    } = ui;
     match query.iter_mut().filter(|(x, _, _, _)| **x == slot).nth(0) {
         Some((_, mut image, mut visibility, entity)) => {
-            image.texture = card.image_handle.clone();
+            image.texture = match face_down {
+                true => get_card_back_image(card_backs, CardBackType::CardType(card.card_type)),
+                false => card.image_handle.clone(),
+            };
             *visibility = Visibility::Visible;
-            for (idx, decendant) in child_query.iter_descendants(entity).enumerate() {
-                for grand_decendant in child_query.iter_descendants(decendant) {
-                    if idx == 0 {
-                        text_query.get_mut(grand_decendant).unwrap().sections[0].value =
-                            card.text.clone();
+            for child in child_query.get(entity).iter().flat_map(|x| x.iter()) {
+                if let Ok(mut x) = overlay_query.get_mut(*child) {
+                    *x = match face_down {
+                        true => Visibility::Hidden,
+                        false => Visibility::Inherited,
                     }
-                    if idx == 1 {
-                        text_query.get_mut(grand_decendant).unwrap().sections[0].value =
+                }
+            }
+            for decendant in child_query.iter_descendants(entity) {
+                if let Ok((pip, mut background)) = pip_query.get_mut(decendant) {
+                    let color = match pip {
+                        CardColorPipMarker::First => card.colors.first(),
+                        CardColorPipMarker::Last => card.colors.last(),
+                    };
+                    background.0 = color.map(|x| x.ui_color()).unwrap_or(Color::NONE);
+                }
+                if let Ok(mut icon) = type_icon_query.get_mut(decendant) {
+                    icon.texture =
+                        get_card_back_image(card_backs, CardBackType::CardType(card.card_type));
+                }
+                if let Ok(mut gem) = gem_query.get_mut(decendant) {
+                    gem.0 = card.rarity.ui_color();
+                }
+                if let Ok(mut text) = equipment_text_query.get_mut(decendant) {
+                    text.sections[0].value = stats
+                        .equipment
+                        .iter()
+                        .map(|x| format!("+ {}\n", x))
+                        .collect();
+                }
+                if let Ok((marker, mut text)) = text_query.get_mut(decendant) {
+                    text.sections[0].value = match marker {
+                        CardTextMarker::Text => card.text.clone(),
+                        CardTextMarker::Hp => {
                             stats.hp.map(|hp| hp.to_string()).unwrap_or("".to_string())
-                    }
+                        }
+                        CardTextMarker::Damage => equipped
+                            .get_damage()
+                            .map(|x| x.to_string())
+                            .unwrap_or("".to_string()),
+                    };
                 }
             }
         }
@@ -321,9 +645,9 @@ fn remove_card(
 fn apply_card_modifications(
     mut game_ui_controller_query: Query<&mut GameController>,
     cards: Res<Assets<Card>>,
-    mut query: Query<(&CardSlot, &mut UiImage, &mut Visibility, Entity)>,
-    child_query: Query<&mut Children>,
-    mut text_query: Query<&mut Text>,
+    mut slot_ui: CardSlotUi,
+    launch_options: Res<LaunchOptions>,
+    current_turn_team: Res<State<CurrentTurnTeam>>,
 ) {
     let mut game_ui_controller = match game_ui_controller_query.get_single_mut() {
         Ok(x) => x,
@@ -335,31 +659,35 @@ fn apply_card_modifications(
     for modification in game_ui_controller.card_modifications.clone() {
         match modification {
             ModifyCardAction::Push { slot, card, stats } => {
+                let equipped = game_ui_controller.equip(cards.get(card).unwrap(), &stats, &cards);
                 push_card(
-                    &mut query,
-                    &child_query,
-                    &mut text_query,
-                    &cards.get(card).unwrap(),
-                    &stats,
+                    &mut slot_ui,
+                    &equipped,
                     slot.clone(),
+                    is_face_down(&slot, &launch_options, current_turn_team.get().0),
                 );
             }
             ModifyCardAction::Remove { slot } => {
-                remove_card(&mut query, slot.clone());
+                remove_card(&mut slot_ui.query, slot.clone());
             }
         }
     }
     game_ui_controller.card_modifications.clear();
-    game_ui_controller.stack_cards(Team::Red, CardSlotType::Play);
-    game_ui_controller.stack_cards(Team::Blue, CardSlotType::Play);
 }
 
 fn spawn_game_ui_controller(
     mut commands: Commands,
     cards: Res<Assets<Card>>,
     mut rng: ResMut<GlobalEntropy<WyRand>>,
+    launch_options: Res<LaunchOptions>,
+    rules: Res<MatchRules>,
+    replay_viewer: Option<ResMut<ReplayViewer>>,
 ) {
-    commands.spawn(GameController::new(&cards, &mut rng));
+    match (&launch_options.network, replay_viewer) {
+        (_, Some(mut viewer)) => commands.spawn(viewer.start_board(&cards)),
+        (NetworkRole::Join(_), None) => commands.spawn(GameController::empty(&cards, &rules)),
+        _ => commands.spawn(GameController::new(&cards, &rules, rng.as_mut())),
+    };
 }
 
 fn update_team_health(
@@ -398,6 +726,28 @@ fn update_team_health(
     }
 }
 
+fn update_energy(
+    game_ui_controller_query: Query<&GameController>,
+    mut energy_query: Query<&mut Text, With<EnergyMarker>>,
+) {
+    let game_ui_controller = match game_ui_controller_query.get_single() {
+        Ok(x) => x,
+        _ => {
+            return;
+        }
+    };
+    // hidden entirely when the match plays one card per turn
+    let value = match game_ui_controller.get_energy() {
+        Some(energy) => format!("Energy {}/{}", energy.current, energy.max),
+        None => String::new(),
+    };
+    for mut text in energy_query.iter_mut() {
+        if text.sections[0].value != value {
+            text.sections[0].value = value.clone();
+        }
+    }
+}
+
 pub struct GameUiControllerPlugin;
 
 impl Plugin for GameUiControllerPlugin {
@@ -406,6 +756,9 @@ impl Plugin for GameUiControllerPlugin {
             OnEnter(LoadState::Loaded),
             (spawn_game_ui_controller, spawn_game_ui),
         )
-        .add_systems(Update, (apply_card_modifications, update_team_health));
+        .add_systems(
+            Update,
+            (apply_card_modifications, update_team_health, update_energy),
+        );
     }
 }
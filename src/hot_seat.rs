@@ -0,0 +1,149 @@
+use crate::assets::LoadState;
+use crate::game_state::{CardSlot, CardSlotType, CurrentTurnTeam, Team};
+use crate::game_ui_controller::GameController;
+use crate::input_map::{InputAction, InputMap};
+use crate::launch_options::{LaunchOptions, NetworkRole};
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+
+#[derive(Resource, Default, Clone, PartialEq, Eq, Debug)]
+pub struct PassDevice {
+    pub waiting_for: Option<Team>,
+}
+
+#[derive(Component, Clone, PartialEq, Eq, PartialOrd, Ord, Reflect)]
+pub struct PassDeviceMarker;
+
+// hot seat hides the waiting team's hand, a networked game hides the hand of the other machine
+pub fn is_face_down(slot: &CardSlot, launch_options: &LaunchOptions, current_team: Team) -> bool {
+    if slot.slot_type != CardSlotType::Hand {
+        return false;
+    }
+    match launch_options.network {
+        NetworkRole::Local => {
+            (launch_options.hot_seat && slot.team != current_team)
+                || launch_options.ai_team == Some(slot.team)
+        }
+        _ => !launch_options.controls_team(slot.team),
+    }
+}
+
+fn spawn_pass_device_screen(mut commands: Commands, assets: Res<AssetServer>) {
+    commands
+        .spawn(ButtonBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+            background_color: BackgroundColor(Color::rgb(0.08, 0.0, 0.07)),
+            focus_policy: FocusPolicy::Block,
+            visibility: Visibility::Hidden,
+            z_index: ZIndex::Global(4),
+            ..default()
+        })
+        .insert(PassDeviceMarker)
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "",
+                TextStyle {
+                    font: assets.load("ui/simple-pixel.ttf"),
+                    font_size: 32.0,
+                    color: Color::WHITE,
+                },
+            ));
+        });
+}
+
+fn start_pass_device(
+    launch_options: Res<LaunchOptions>,
+    current_turn_team: Res<State<CurrentTurnTeam>>,
+    mut pass_device: ResMut<PassDevice>,
+    mut game_ui_controller_query: Query<&mut GameController>,
+) {
+    if !launch_options.hot_seat {
+        return;
+    }
+    pass_device.waiting_for = Some(current_turn_team.get().0);
+    // re-render both hands so the face down side follows the current team
+    if let Ok(mut x) = game_ui_controller_query.get_single_mut() {
+        x.refresh_cards(Team::Red, CardSlotType::Hand);
+        x.refresh_cards(Team::Blue, CardSlotType::Hand);
+    }
+}
+
+fn finish_pass_device(
+    mut keys: ResMut<ButtonInput<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    mut gamepad_buttons: ResMut<ButtonInput<GamepadButton>>,
+    input_map: Res<InputMap>,
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<PassDeviceMarker>)>,
+    mut pass_device: ResMut<PassDevice>,
+) {
+    if pass_device.waiting_for.is_none() {
+        return;
+    }
+    let confirmed = interaction_query.iter().any(|x| *x == Interaction::Pressed)
+        || [InputAction::PickUp, InputAction::Place]
+            .into_iter()
+            .any(|x| input_map.just_pressed(x, &keys, &gamepads, &gamepad_buttons));
+    if !confirmed {
+        return;
+    }
+    pass_device.waiting_for = None;
+    // the confirm press must not also pick up or place a card this frame
+    for action in [InputAction::PickUp, InputAction::Place] {
+        input_map.consume(action, &mut keys, &gamepads, &mut gamepad_buttons);
+    }
+}
+
+fn update_pass_device_screen(
+    pass_device: Res<PassDevice>,
+    input_map: Res<InputMap>,
+    mut screen_query: Query<(&mut Visibility, &Children), With<PassDeviceMarker>>,
+    mut text_query: Query<&mut Text>,
+) {
+    if !pass_device.is_changed() {
+        return;
+    }
+    for (mut visibility, children) in screen_query.iter_mut() {
+        let team = match pass_device.waiting_for {
+            Some(x) => x,
+            None => {
+                *visibility = Visibility::Hidden;
+                continue;
+            }
+        };
+        *visibility = Visibility::Visible;
+        for child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(*child) {
+                text.sections[0].value = format!(
+                    "Pass the device to {:?}\nclick or press {} to continue",
+                    team,
+                    input_map.describe(InputAction::PickUp)
+                )
+            }
+        }
+    }
+}
+
+pub struct HotSeatPlugin;
+
+impl Plugin for HotSeatPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PassDevice>()
+            .add_systems(OnEnter(LoadState::Loaded), spawn_pass_device_screen)
+            .add_systems(
+                Update,
+                (
+                    start_pass_device.run_if(state_changed::<CurrentTurnTeam>),
+                    finish_pass_device,
+                    update_pass_device_screen,
+                )
+                    .chain(),
+            );
+    }
+}
@@ -0,0 +1,322 @@
+use bevy::asset::ron;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+const SETTINGS_FILE_NAME: &str = "settings.ron";
+
+#[derive(
+    Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Reflect,
+)]
+pub enum InputAction {
+    Draw,
+    PickUp,
+    Place,
+    Cancel,
+    Undo,
+    NextSlot,
+    PrevSlot,
+    NextRow,
+    PrevRow,
+    EndTurn,
+    OpenLog,
+}
+
+impl InputAction {
+    pub const ALL: [InputAction; 11] = [
+        InputAction::Draw,
+        InputAction::PickUp,
+        InputAction::Place,
+        InputAction::Cancel,
+        InputAction::Undo,
+        InputAction::NextSlot,
+        InputAction::PrevSlot,
+        InputAction::NextRow,
+        InputAction::PrevRow,
+        InputAction::EndTurn,
+        InputAction::OpenLog,
+    ];
+
+    fn default_binding(&self) -> InputBinding {
+        let (keys, gamepad_buttons) = match self {
+            InputAction::Draw => (vec![KeyCode::KeyE], vec![GamepadButtonType::North]),
+            InputAction::PickUp | InputAction::Place => (
+                vec![KeyCode::Enter, KeyCode::Space],
+                vec![GamepadButtonType::South],
+            ),
+            InputAction::Cancel => (
+                vec![KeyCode::Escape, KeyCode::Backspace],
+                vec![GamepadButtonType::East],
+            ),
+            InputAction::Undo => (vec![KeyCode::KeyZ], vec![GamepadButtonType::LeftTrigger]),
+            InputAction::NextSlot => (
+                vec![KeyCode::ArrowRight, KeyCode::KeyD],
+                vec![GamepadButtonType::DPadRight],
+            ),
+            InputAction::PrevSlot => (
+                vec![KeyCode::ArrowLeft, KeyCode::KeyA],
+                vec![GamepadButtonType::DPadLeft],
+            ),
+            InputAction::NextRow => (
+                vec![KeyCode::ArrowDown, KeyCode::KeyS],
+                vec![GamepadButtonType::DPadDown],
+            ),
+            InputAction::PrevRow => (
+                vec![KeyCode::ArrowUp, KeyCode::KeyW],
+                vec![GamepadButtonType::DPadUp],
+            ),
+            InputAction::EndTurn => (vec![KeyCode::KeyQ], vec![GamepadButtonType::West]),
+            InputAction::OpenLog => (vec![KeyCode::KeyL], vec![GamepadButtonType::Select]),
+        };
+        InputBinding {
+            keys,
+            gamepad_buttons,
+        }
+    }
+}
+
+// takes an input off a binding and hands it the inputs it is swapped for instead
+fn swap_input<T: Copy + PartialEq>(inputs: &mut Vec<T>, taken: T, swapped: &[T]) {
+    inputs.retain(|x| *x != taken);
+    for x in swapped {
+        if *x != taken && !inputs.contains(x) {
+            inputs.push(*x);
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug, Default)]
+pub struct InputBinding {
+    pub keys: Vec<KeyCode>,
+    pub gamepad_buttons: Vec<GamepadButtonType>,
+}
+
+#[derive(Resource, Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct InputMap {
+    pub bindings: BTreeMap<InputAction, InputBinding>,
+}
+
+impl Default for InputMap {
+    fn default() -> Self {
+        InputMap {
+            bindings: InputAction::ALL
+                .iter()
+                .map(|action| (*action, action.default_binding()))
+                .collect(),
+        }
+    }
+}
+
+impl InputMap {
+    pub fn get_binding(&self, action: InputAction) -> InputBinding {
+        self.bindings
+            .get(&action)
+            .cloned()
+            .unwrap_or_else(|| action.default_binding())
+    }
+
+    // the key replaces the action's keys, any other action bound to it gets those keys instead
+    pub fn rebind_key(&mut self, action: InputAction, key: KeyCode) {
+        let mut binding = self.get_binding(action);
+        for (other, x) in self.bindings.iter_mut() {
+            if *other != action && x.keys.contains(&key) {
+                swap_input(&mut x.keys, key, &binding.keys);
+            }
+        }
+        binding.keys = vec![key];
+        self.bindings.insert(action, binding);
+    }
+
+    // the button replaces the action's buttons, any other action bound to it gets those buttons
+    pub fn rebind_gamepad_button(&mut self, action: InputAction, button: GamepadButtonType) {
+        let mut binding = self.get_binding(action);
+        for (other, x) in self.bindings.iter_mut() {
+            if *other != action && x.gamepad_buttons.contains(&button) {
+                swap_input(&mut x.gamepad_buttons, button, &binding.gamepad_buttons);
+            }
+        }
+        binding.gamepad_buttons = vec![button];
+        self.bindings.insert(action, binding);
+    }
+
+    pub fn just_pressed(
+        &self,
+        action: InputAction,
+        keys: &ButtonInput<KeyCode>,
+        gamepads: &Gamepads,
+        gamepad_buttons: &ButtonInput<GamepadButton>,
+    ) -> bool {
+        let binding = self.get_binding(action);
+        keys.any_just_pressed(binding.keys.iter().copied())
+            || gamepads.iter().any(|gamepad| {
+                binding
+                    .gamepad_buttons
+                    .iter()
+                    .any(|x| gamepad_buttons.just_pressed(GamepadButton::new(gamepad, *x)))
+            })
+    }
+
+    // stops later systems this frame from seeing the press of an action
+    pub fn consume(
+        &self,
+        action: InputAction,
+        keys: &mut ButtonInput<KeyCode>,
+        gamepads: &Gamepads,
+        gamepad_buttons: &mut ButtonInput<GamepadButton>,
+    ) {
+        let binding = self.get_binding(action);
+        for key in binding.keys {
+            keys.clear_just_pressed(key);
+        }
+        for gamepad in gamepads.iter() {
+            for button in binding.gamepad_buttons.iter() {
+                gamepad_buttons.clear_just_pressed(GamepadButton::new(gamepad, *button));
+            }
+        }
+    }
+
+    pub fn get_just_pressed_actions(
+        &self,
+        keys: &ButtonInput<KeyCode>,
+        gamepads: &Gamepads,
+        gamepad_buttons: &ButtonInput<GamepadButton>,
+    ) -> Vec<InputAction> {
+        InputAction::ALL
+            .into_iter()
+            .filter(|action| self.just_pressed(*action, keys, gamepads, gamepad_buttons))
+            .collect()
+    }
+
+    pub fn describe(&self, action: InputAction) -> String {
+        let binding = self.get_binding(action);
+        binding
+            .keys
+            .iter()
+            .map(|x| format!("{:?}", x))
+            .chain(binding.gamepad_buttons.iter().map(|x| format!("{:?}", x)))
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+}
+
+// the input resources the bound actions are read from
+#[derive(SystemParam)]
+pub struct ActionInputs<'w> {
+    keys: Res<'w, ButtonInput<KeyCode>>,
+    gamepads: Res<'w, Gamepads>,
+    gamepad_buttons: Res<'w, ButtonInput<GamepadButton>>,
+    input_map: Res<'w, InputMap>,
+}
+
+impl ActionInputs<'_> {
+    pub fn get_just_pressed_actions(&self) -> Vec<InputAction> {
+        self.input_map
+            .get_just_pressed_actions(&self.keys, &self.gamepads, &self.gamepad_buttons)
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct SettingsFile {
+    #[serde(default)]
+    input_map: InputMap,
+}
+
+pub fn get_settings_path() -> PathBuf {
+    std::env::current_exe()
+        .map(|x| x.with_file_name(SETTINGS_FILE_NAME))
+        .unwrap_or(PathBuf::from(SETTINGS_FILE_NAME))
+}
+
+pub fn load_input_map() -> InputMap {
+    let path = get_settings_path();
+    let mut input_map = match std::fs::read_to_string(&path) {
+        Ok(x) => match ron::de::from_str::<SettingsFile>(&x) {
+            Ok(settings) => settings.input_map,
+            Err(err) => {
+                warn!("failed to parse {}: {}", path.display(), err);
+                InputMap::default()
+            }
+        },
+        Err(_) => InputMap::default(),
+    };
+    // actions added after the file was written fall back to their defaults
+    for action in InputAction::ALL {
+        input_map
+            .bindings
+            .entry(action)
+            .or_insert_with(|| action.default_binding());
+    }
+    input_map
+}
+
+pub fn save_input_map(input_map: &InputMap) {
+    let path = get_settings_path();
+    let settings = SettingsFile {
+        input_map: input_map.clone(),
+    };
+    match ron::ser::to_string_pretty(&settings, ron::ser::PrettyConfig::default()) {
+        Ok(x) => match std::fs::write(&path, x) {
+            Ok(_) => {}
+            Err(err) => warn!("failed to write {}: {}", path.display(), err),
+        },
+        Err(err) => warn!("failed to serialize settings: {}", err),
+    }
+}
+
+pub struct InputMapPlugin;
+
+impl Plugin for InputMapPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(load_input_map());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rebinding_replaces_the_actions_keys() {
+        let mut input_map = InputMap::default();
+        input_map.rebind_key(InputAction::Draw, KeyCode::KeyR);
+        assert_eq!(
+            input_map.get_binding(InputAction::Draw).keys,
+            vec![KeyCode::KeyR]
+        );
+    }
+
+    #[test]
+    fn rebinding_a_taken_key_swaps_it() {
+        let mut input_map = InputMap::default();
+        input_map.rebind_key(InputAction::Draw, KeyCode::KeyQ);
+        assert_eq!(
+            input_map.get_binding(InputAction::Draw).keys,
+            vec![KeyCode::KeyQ]
+        );
+        assert_eq!(
+            input_map.get_binding(InputAction::EndTurn).keys,
+            vec![KeyCode::KeyE]
+        );
+        input_map.rebind_gamepad_button(InputAction::Draw, GamepadButtonType::West);
+        assert_eq!(
+            input_map.get_binding(InputAction::EndTurn).gamepad_buttons,
+            vec![GamepadButtonType::North]
+        );
+    }
+
+    #[test]
+    fn rebinding_a_shared_key_keeps_the_rest_of_the_binding() {
+        let mut input_map = InputMap::default();
+        input_map.rebind_key(InputAction::PickUp, KeyCode::Space);
+        assert_eq!(
+            input_map.get_binding(InputAction::PickUp).keys,
+            vec![KeyCode::Space]
+        );
+        assert_eq!(
+            input_map.get_binding(InputAction::Place).keys,
+            vec![KeyCode::Enter]
+        );
+    }
+}
@@ -0,0 +1,94 @@
+use crate::game_state::Team;
+use bevy::prelude::*;
+use std::path::PathBuf;
+
+pub const HOST_TEAM: Team = Team::Red;
+pub const JOIN_TEAM: Team = Team::Blue;
+
+#[derive(Default, Clone, PartialEq, Eq, Debug)]
+pub enum NetworkRole {
+    #[default]
+    Local,
+    Host(String),
+    Join(String),
+}
+
+#[derive(Resource, Default, Clone, PartialEq, Eq, Debug)]
+pub struct LaunchOptions {
+    pub hot_seat: bool,
+    pub network: NetworkRole,
+    pub seed: Option<u64>,
+    pub rules: Option<PathBuf>,
+    pub record: Option<PathBuf>,
+    pub replay: Option<PathBuf>,
+    // a local match where this team is played by the computer
+    pub ai_team: Option<Team>,
+}
+
+impl LaunchOptions {
+    pub fn from_args(args: impl Iterator<Item = String>) -> Self {
+        let mut options = LaunchOptions::default();
+        let mut args = args.skip(1);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--hot-seat" => options.hot_seat = true,
+                "--host" => match args.next() {
+                    Some(address) => options.network = NetworkRole::Host(address),
+                    None => eprintln!("--host expects an address such as 0.0.0.0:7777"),
+                },
+                "--join" => match args.next() {
+                    Some(address) => options.network = NetworkRole::Join(address),
+                    None => eprintln!("--join expects an address such as 127.0.0.1:7777"),
+                },
+                "--seed" => match args.next().and_then(|x| x.parse().ok()) {
+                    Some(seed) => options.seed = Some(seed),
+                    None => eprintln!("--seed expects a number"),
+                },
+                "--rules" => match args.next() {
+                    Some(path) => options.rules = Some(PathBuf::from(path)),
+                    None => eprintln!("--rules expects a .ron file"),
+                },
+                "--record" => match args.next() {
+                    Some(path) => options.record = Some(PathBuf::from(path)),
+                    None => eprintln!("--record expects a .replay.ron file to write"),
+                },
+                "--replay" => match args.next() {
+                    Some(path) => options.replay = Some(PathBuf::from(path)),
+                    None => eprintln!("--replay expects a .replay.ron file to watch"),
+                },
+                "--ai" => match args.next().as_deref() {
+                    Some("red") => options.ai_team = Some(Team::Red),
+                    Some("blue") => options.ai_team = Some(Team::Blue),
+                    _ => eprintln!("--ai expects the team to play, red or blue"),
+                },
+                _ => eprintln!("ignoring unknown argument {}", arg),
+            }
+        }
+        options
+    }
+
+    // whether input on this machine may act for the team
+    pub fn controls_team(&self, team: Team) -> bool {
+        if self.is_replay() {
+            return false;
+        }
+        match self.network {
+            NetworkRole::Local => self.ai_team != Some(team),
+            NetworkRole::Host(_) => team == HOST_TEAM,
+            NetworkRole::Join(_) => team == JOIN_TEAM,
+        }
+    }
+
+    pub fn is_replay(&self) -> bool {
+        self.replay.is_some()
+    }
+
+    // the computer only plays in local matches, replays already contain its moves
+    pub fn has_ai_player(&self) -> bool {
+        self.ai_team.is_some() && self.network == NetworkRole::Local && !self.is_replay()
+    }
+
+    pub fn is_network_client(&self) -> bool {
+        matches!(self.network, NetworkRole::Join(_))
+    }
+}
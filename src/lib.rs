@@ -0,0 +1,38 @@
+extern crate bevy;
+extern crate bevy_inspector_egui;
+extern crate bevy_rand;
+extern crate serde;
+pub mod ai;
+pub mod assets;
+pub mod card_pool;
+pub mod card_preview;
+pub mod card_stats;
+pub mod cards;
+pub mod color_chains;
+pub mod constants;
+pub mod custom_cursor;
+pub mod equipment;
+pub mod focus;
+pub mod game;
+pub mod game_state;
+pub mod game_ui_controller;
+pub mod hot_seat;
+pub mod input_map;
+pub mod launch_options;
+pub mod match_log;
+pub mod mulligan;
+pub mod network;
+pub mod protocol;
+pub mod replay;
+pub mod ron_asset_macro;
+pub mod rules;
+pub mod settings_screen;
+pub mod simulation;
+pub mod spawn_ui;
+pub mod status_effects;
+pub mod triggers;
+pub mod turn_hud;
+pub mod turn_timer;
+
+#[macro_use]
+extern crate num_derive;
@@ -1,33 +1,28 @@
-extern crate bevy;
-extern crate bevy_inspector_egui;
-extern crate bevy_rand;
-extern crate serde;
-use assets::AssetLoaderPlugin;
 use bevy::diagnostic::{FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin};
 use bevy::prelude::*;
 use bevy_inspector_egui::quick::WorldInspectorPlugin;
-mod cards;
-mod ron_asset_macro;
 use bevy_rand::prelude::{EntropyPlugin, WyRand};
-mod game;
-use game::GameUIPlugin;
-mod assets;
-mod constants;
-mod custom_cursor;
-mod game_state;
-mod game_ui_controller;
-mod spawn_ui;
+use project_color_card::assets::AssetLoaderPlugin;
+use project_color_card::game::GameUIPlugin;
+use project_color_card::launch_options::LaunchOptions;
+use project_color_card::rules::{load_match_rules, MatchRules, MatchSeed};
 
-#[macro_use]
-extern crate num_derive;
 fn main() {
+    let launch_options = LaunchOptions::from_args(std::env::args());
+    let seed = launch_options.seed.unwrap_or_else(rand::random);
     App::new()
+        .insert_resource(launch_options.clone())
+        .insert_resource(MatchSeed(seed))
         .add_plugins(AssetLoaderPlugin)
         .add_plugins(DefaultPlugins.set(ImagePlugin::default_nearest()))
+        .insert_resource(match &launch_options.rules {
+            Some(path) => load_match_rules(path),
+            None => MatchRules::default(),
+        })
         .add_plugins(LogDiagnosticsPlugin::default())
         .add_plugins(FrameTimeDiagnosticsPlugin::default())
         .add_plugins(WorldInspectorPlugin::new())
-        .add_plugins(EntropyPlugin::<WyRand>::default())
+        .add_plugins(EntropyPlugin::<WyRand>::with_seed(seed.to_le_bytes()))
         .add_plugins(GameUIPlugin)
         .add_systems(Startup, setup)
         .run();
@@ -0,0 +1,93 @@
+use crate::assets::LoadState;
+use bevy::prelude::*;
+
+const VISIBLE_LOG_ENTRIES: usize = 24;
+
+#[derive(Resource, Default, Clone, Debug)]
+pub struct MatchLog {
+    entries: Vec<String>,
+    pub open: bool,
+}
+
+impl MatchLog {
+    pub fn push(&mut self, entry: impl Into<String>) {
+        let entry = entry.into();
+        info!("{}", entry);
+        self.entries.push(entry);
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+}
+
+#[derive(Component, Clone, PartialEq, Eq, PartialOrd, Ord, Reflect)]
+pub struct MatchLogMarker;
+
+fn spawn_match_log(mut commands: Commands, assets: Res<AssetServer>) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                right: Val::Px(0.0),
+                top: Val::Px(0.0),
+                width: Val::Percent(30.0),
+                max_height: Val::Percent(100.0),
+                padding: UiRect::all(Val::Px(8.0)),
+                overflow: Overflow::clip(),
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+            background_color: BackgroundColor(Color::rgba(0.08, 0.0, 0.07, 0.85)),
+            visibility: Visibility::Hidden,
+            z_index: ZIndex::Global(2),
+            ..default()
+        })
+        .insert(MatchLogMarker)
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "",
+                TextStyle {
+                    font: assets.load("ui/simple-pixel.ttf"),
+                    font_size: 14.0,
+                    color: Color::WHITE,
+                },
+            ));
+        });
+}
+
+fn update_match_log(
+    match_log: Res<MatchLog>,
+    mut log_query: Query<(&mut Visibility, &Children), With<MatchLogMarker>>,
+    mut text_query: Query<&mut Text>,
+) {
+    if !match_log.is_changed() {
+        return;
+    }
+    for (mut visibility, children) in log_query.iter_mut() {
+        *visibility = match match_log.open {
+            true => Visibility::Visible,
+            false => Visibility::Hidden,
+        };
+        let entries = match_log.entries();
+        let first = entries.len().saturating_sub(VISIBLE_LOG_ENTRIES);
+        for child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(*child) {
+                text.sections[0].value = entries[first..].join("\n")
+            }
+        }
+    }
+}
+
+pub struct MatchLogPlugin;
+
+impl Plugin for MatchLogPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MatchLog>()
+            .add_systems(OnEnter(LoadState::Loaded), spawn_match_log)
+            .add_systems(Update, update_match_log);
+    }
+}
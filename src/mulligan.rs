@@ -0,0 +1,120 @@
+use crate::game_state::{
+    CardSlot, CardSlotPressed, CardSlotType, EndTurnButtonMarker, EndTurnPressed,
+    PlayerActionEvent, TurnState,
+};
+use crate::game_ui_controller::GameController;
+use crate::launch_options::LaunchOptions;
+use crate::protocol::PlayerAction;
+use bevy::prelude::*;
+use std::collections::BTreeSet;
+
+const RETURNED_CARD_TINT: Color = Color::rgb(0.4, 0.4, 0.4);
+
+// the hand slots the current team marked to send back
+#[derive(Resource, Default, Clone, PartialEq, Eq, Debug)]
+pub struct MulliganSelection {
+    pub slots: BTreeSet<usize>,
+}
+
+fn select_mulligan_cards(
+    mut slot_pressed: EventReader<CardSlotPressed>,
+    mut end_turn_pressed: EventReader<EndTurnPressed>,
+    game_ui_controller_query: Query<&GameController>,
+    launch_options: Res<LaunchOptions>,
+    mut selection: ResMut<MulliganSelection>,
+    mut player_actions: EventWriter<PlayerActionEvent>,
+) {
+    let game_ui_controller = match game_ui_controller_query.get_single() {
+        Ok(x) => x,
+        _ => {
+            return;
+        }
+    };
+    let team = game_ui_controller.get_current_team();
+    if game_ui_controller.get_turn_state() != TurnState::Mulligan
+        || !launch_options.controls_team(team)
+    {
+        slot_pressed.clear();
+        end_turn_pressed.clear();
+        if !selection.slots.is_empty() {
+            selection.slots.clear();
+        }
+        return;
+    }
+    for CardSlotPressed(slot) in slot_pressed.read() {
+        if slot.team != team
+            || slot.slot_type != CardSlotType::Hand
+            || game_ui_controller.get_card(slot).is_none()
+        {
+            continue;
+        }
+        if !selection.slots.remove(&slot.id) {
+            selection.slots.insert(slot.id);
+        }
+    }
+    // ending the turn confirms the selection, nothing selected keeps the hand
+    if end_turn_pressed.read().count() == 0 {
+        return;
+    }
+    player_actions.send(PlayerActionEvent {
+        team,
+        action: PlayerAction::Mulligan {
+            hand_slots: selection.slots.iter().copied().collect(),
+        },
+    });
+    selection.slots.clear();
+}
+
+fn show_mulligan_selection(
+    selection: Res<MulliganSelection>,
+    game_ui_controller_query: Query<&GameController>,
+    mut card_query: Query<(&CardSlot, &mut BackgroundColor)>,
+    end_turn_button_query: Query<&Children, With<EndTurnButtonMarker>>,
+    mut text_query: Query<&mut Text>,
+) {
+    let game_ui_controller = match game_ui_controller_query.get_single() {
+        Ok(x) => x,
+        _ => {
+            return;
+        }
+    };
+    let team = game_ui_controller.get_current_team();
+    for (slot, mut background) in card_query.iter_mut() {
+        let tint = match slot.team == team
+            && slot.slot_type == CardSlotType::Hand
+            && selection.slots.contains(&slot.id)
+        {
+            true => RETURNED_CARD_TINT,
+            false => Color::WHITE,
+        };
+        if background.0 != tint {
+            background.0 = tint;
+        }
+    }
+    let label = match (game_ui_controller.get_turn_state(), selection.slots.len()) {
+        (TurnState::Mulligan, 0) => "Keep hand".to_string(),
+        (TurnState::Mulligan, count) => format!("Redraw {}", count),
+        _ => "End turn".to_string(),
+    };
+    for children in end_turn_button_query.iter() {
+        for child in children.iter() {
+            match text_query.get_mut(*child) {
+                Ok(mut text) if text.sections[0].value != label => {
+                    text.sections[0].value = label.clone()
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+pub struct MulliganPlugin;
+
+impl Plugin for MulliganPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MulliganSelection>().add_systems(
+            Update,
+            (select_mulligan_cards, show_mulligan_selection).chain(),
+        );
+    }
+}
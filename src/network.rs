@@ -0,0 +1,349 @@
+use crate::cards::Card;
+use crate::game_state::{ActionRejected, PlayerActionEvent, StateDeltaEvent, Team};
+use crate::game_ui_controller::GameController;
+use crate::launch_options::{LaunchOptions, NetworkRole, JOIN_TEAM};
+use crate::match_log::MatchLog;
+use crate::protocol::{decode_binary, encode_binary, PlayerAction, ProtocolError, StateDelta};
+use bevy::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::io::{ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_millis(500);
+const RECONNECT_SECONDS: f32 = 1.0;
+// a frame can never be larger than this, anything bigger is treated as garbage
+const MAX_INCOMING_BYTES: usize = 1 << 20;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+enum ClientMessage {
+    Action(PlayerAction),
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+enum HostMessage {
+    Welcome { team: Team },
+    Delta(StateDelta),
+    Rejected(String),
+}
+
+// each message is a little endian u32 length followed by the versioned binary encoding
+struct Connection {
+    stream: TcpStream,
+    incoming: Vec<u8>,
+    outgoing: Vec<u8>,
+}
+
+impl Connection {
+    fn new(stream: TcpStream) -> std::io::Result<Self> {
+        stream.set_nonblocking(true)?;
+        stream.set_nodelay(true)?;
+        Ok(Connection {
+            stream,
+            incoming: vec![],
+            outgoing: vec![],
+        })
+    }
+
+    fn queue<T: Serialize>(&mut self, message: &T) {
+        let bytes = encode_binary(message);
+        self.outgoing.extend((bytes.len() as u32).to_le_bytes());
+        self.outgoing.extend(bytes);
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        while !self.outgoing.is_empty() {
+            match self.stream.write(&self.outgoing) {
+                Ok(0) => return Err(ErrorKind::WriteZero.into()),
+                Ok(written) => {
+                    self.outgoing.drain(..written);
+                }
+                Err(err) if err.kind() == ErrorKind::WouldBlock => return Ok(()),
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(())
+    }
+
+    fn receive<T: DeserializeOwned>(&mut self) -> std::io::Result<Vec<T>> {
+        let mut buffer = [0u8; 4096];
+        // whatever does not fit stays in the socket until the frames before it are decoded
+        while self.incoming.len() < MAX_INCOMING_BYTES {
+            let space = buffer.len().min(MAX_INCOMING_BYTES - self.incoming.len());
+            match self.stream.read(&mut buffer[..space]) {
+                Ok(0) => return Err(ErrorKind::UnexpectedEof.into()),
+                Ok(read) => self.incoming.extend(&buffer[..read]),
+                Err(err) if err.kind() == ErrorKind::WouldBlock => break,
+                Err(err) => return Err(err),
+            }
+        }
+        let mut messages = vec![];
+        while self.incoming.len() >= 4 {
+            let length = u32::from_le_bytes(self.incoming[..4].try_into().unwrap()) as usize;
+            if 4 + length > MAX_INCOMING_BYTES {
+                let err = ProtocolError::Malformed(format!("{} byte frame is too large", length));
+                return Err(std::io::Error::new(ErrorKind::InvalidData, err.to_string()));
+            }
+            if self.incoming.len() < 4 + length {
+                break;
+            }
+            let frame: Vec<u8> = self.incoming.drain(..4 + length).skip(4).collect();
+            match decode_binary::<T>(&frame) {
+                Ok(x) => messages.push(x),
+                // the other side speaks a different protocol, nothing after this can be trusted
+                Err(err @ ProtocolError::VersionMismatch { .. }) => {
+                    return Err(std::io::Error::new(ErrorKind::InvalidData, err.to_string()))
+                }
+                Err(err) => warn!("ignoring network message: {}", err),
+            }
+        }
+        Ok(messages)
+    }
+}
+
+#[derive(Resource, Default)]
+struct NetworkSession {
+    listener: Option<TcpListener>,
+    connection: Option<Connection>,
+    reconnect_timer: Timer,
+}
+
+fn start_network(launch_options: Res<LaunchOptions>, mut session: ResMut<NetworkSession>) {
+    session.reconnect_timer = Timer::from_seconds(RECONNECT_SECONDS, TimerMode::Repeating);
+    let address = match &launch_options.network {
+        NetworkRole::Host(x) => x,
+        _ => {
+            return;
+        }
+    };
+    match TcpListener::bind(address).and_then(|x| x.set_nonblocking(true).map(|_| x)) {
+        Ok(x) => {
+            info!("hosting on {}", address);
+            session.listener = Some(x);
+        }
+        Err(err) => error!("failed to host on {}: {}", address, err),
+    }
+}
+
+fn accept_client(
+    mut session: ResMut<NetworkSession>,
+    game_ui_controller_query: Query<&GameController>,
+    cards: Res<Assets<Card>>,
+    match_log: Res<MatchLog>,
+) {
+    if session.connection.is_some() {
+        return;
+    }
+    let game_ui_controller = match game_ui_controller_query.get_single() {
+        Ok(x) => x,
+        _ => {
+            return;
+        }
+    };
+    let stream = match session.listener.as_ref().map(|x| x.accept()) {
+        Some(Ok((stream, address))) => {
+            info!("{} joined", address);
+            stream
+        }
+        _ => {
+            return;
+        }
+    };
+    let mut connection = match Connection::new(stream) {
+        Ok(x) => x,
+        Err(err) => {
+            warn!("failed to set up connection: {}", err);
+            return;
+        }
+    };
+    // a fresh client gets the whole board, later frames only send what changed
+    connection.queue(&HostMessage::Welcome { team: JOIN_TEAM });
+    for entry in match_log.entries() {
+        connection.queue(&HostMessage::Delta(StateDelta::Log(entry.clone())));
+    }
+    for delta in game_ui_controller.snapshot(&cards) {
+        connection.queue(&HostMessage::Delta(delta));
+    }
+    session.connection = Some(connection);
+}
+
+fn receive_client_actions(
+    mut session: ResMut<NetworkSession>,
+    mut player_actions: EventWriter<PlayerActionEvent>,
+) {
+    let connection = match session.connection.as_mut() {
+        Some(x) => x,
+        None => {
+            return;
+        }
+    };
+    match connection.receive::<ClientMessage>() {
+        Ok(messages) => {
+            for ClientMessage::Action(action) in messages {
+                // the remote player can only ever act as the joining team
+                player_actions.send(PlayerActionEvent {
+                    team: JOIN_TEAM,
+                    action,
+                });
+            }
+        }
+        Err(err) => {
+            warn!("client disconnected: {}", err);
+            session.connection = None;
+        }
+    }
+}
+
+fn send_host_updates(
+    mut session: ResMut<NetworkSession>,
+    mut state_deltas: EventReader<StateDeltaEvent>,
+    mut rejected: EventReader<ActionRejected>,
+) {
+    let connection = match session.connection.as_mut() {
+        Some(x) => x,
+        None => {
+            state_deltas.clear();
+            rejected.clear();
+            return;
+        }
+    };
+    for StateDeltaEvent(delta) in state_deltas.read() {
+        connection.queue(&HostMessage::Delta(delta.clone()));
+    }
+    for ActionRejected { team, reason } in rejected.read() {
+        if *team == JOIN_TEAM {
+            connection.queue(&HostMessage::Rejected(reason.clone()));
+        }
+    }
+    match connection.flush() {
+        Ok(_) => {}
+        Err(err) => {
+            warn!("client disconnected: {}", err);
+            session.connection = None;
+        }
+    }
+}
+
+fn connect_to_host(
+    launch_options: Res<LaunchOptions>,
+    time: Res<Time>,
+    mut session: ResMut<NetworkSession>,
+) {
+    if session.connection.is_some() || !session.reconnect_timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    let address = match &launch_options.network {
+        NetworkRole::Join(x) => x,
+        _ => {
+            return;
+        }
+    };
+    let socket_address: Option<SocketAddr> =
+        address.to_socket_addrs().ok().and_then(|mut x| x.next());
+    let stream = match socket_address.map(|x| TcpStream::connect_timeout(&x, CONNECT_TIMEOUT)) {
+        Some(Ok(x)) => x,
+        Some(Err(err)) => {
+            warn!("failed to join {}: {}", address, err);
+            return;
+        }
+        None => {
+            warn!("failed to resolve {}", address);
+            return;
+        }
+    };
+    match Connection::new(stream) {
+        Ok(x) => session.connection = Some(x),
+        Err(err) => warn!("failed to set up connection: {}", err),
+    }
+}
+
+fn receive_host_updates(
+    mut session: ResMut<NetworkSession>,
+    mut game_ui_controller_query: Query<&mut GameController>,
+    mut match_log: ResMut<MatchLog>,
+) {
+    let mut game_ui_controller = match game_ui_controller_query.get_single_mut() {
+        Ok(x) => x,
+        _ => {
+            return;
+        }
+    };
+    let connection = match session.connection.as_mut() {
+        Some(x) => x,
+        None => {
+            return;
+        }
+    };
+    let messages = match connection.receive::<HostMessage>() {
+        Ok(x) => x,
+        Err(err) => {
+            match_log.push(format!("lost connection to the host: {}", err));
+            session.connection = None;
+            return;
+        }
+    };
+    for message in messages {
+        match message {
+            HostMessage::Welcome { team } => match_log.push(format!("joined as {:?}", team)),
+            HostMessage::Delta(delta) => match game_ui_controller.apply_delta(delta) {
+                Ok(_) => {}
+                Err(err) => warn!("failed to apply host update: {}", err),
+            },
+            HostMessage::Rejected(reason) => {
+                match_log.push(format!("the host rejected that: {}", reason))
+            }
+        }
+    }
+}
+
+fn send_client_actions(
+    mut session: ResMut<NetworkSession>,
+    mut player_actions: EventReader<PlayerActionEvent>,
+) {
+    let connection = match session.connection.as_mut() {
+        Some(x) => x,
+        None => {
+            player_actions.clear();
+            return;
+        }
+    };
+    for PlayerActionEvent { action, .. } in player_actions.read() {
+        connection.queue(&ClientMessage::Action(action.clone()));
+    }
+    match connection.flush() {
+        Ok(_) => {}
+        Err(err) => {
+            warn!("lost connection to the host: {}", err);
+            session.connection = None;
+        }
+    }
+}
+
+fn is_host(launch_options: Res<LaunchOptions>) -> bool {
+    matches!(launch_options.network, NetworkRole::Host(_))
+}
+
+fn is_client(launch_options: Res<LaunchOptions>) -> bool {
+    launch_options.is_network_client()
+}
+
+pub struct NetworkPlugin;
+
+impl Plugin for NetworkPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<NetworkSession>()
+            .add_systems(Startup, start_network)
+            .add_systems(
+                Update,
+                (
+                    (accept_client, receive_client_actions, send_host_updates)
+                        .chain()
+                        .run_if(is_host),
+                    (connect_to_host, receive_host_updates, send_client_actions)
+                        .chain()
+                        .run_if(is_client),
+                ),
+            );
+    }
+}
@@ -0,0 +1,335 @@
+use crate::cards::{Card, CardType};
+use crate::constants::CARD_SLOT_COUNT;
+use crate::game_state::{CardSlot, CardSlotType, CardStats, Team, TurnState};
+use crate::game_ui_controller::GameController;
+use crate::rules::MatchRules;
+use bevy::asset::ron;
+use bevy::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// bump whenever PlayerAction, StateDelta or anything they contain changes shape
+pub const PROTOCOL_VERSION: u32 = 2;
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum PlayerAction {
+    // the hand slots to return, an empty list keeps the opening hand
+    Mulligan { hand_slots: Vec<usize> },
+    // pile is the index into the team's offered card types, 0 when there is no choice
+    Draw { pile: usize },
+    // only allowed before drawing with a full hand, making room for the drawn card
+    Discard { hand_slot: usize },
+    Place { hand_slot: usize, play_slot: usize },
+    Undo,
+    EndTurn,
+}
+
+// cards are referenced by name since asset ids are not stable across processes
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct PlacedCard {
+    pub name: String,
+    pub stats: CardStats,
+}
+
+// what the current team has left to spend this turn
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct Energy {
+    pub current: u32,
+    pub max: u32,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum StateDelta {
+    SlotChanged {
+        slot: CardSlot,
+        card: Option<PlacedCard>,
+    },
+    TurnChanged {
+        current_team: Team,
+        turn_state: TurnState,
+    },
+    NextDrawChanged {
+        team: Team,
+        card_types: Vec<CardType>,
+    },
+    TeamHealthChanged {
+        team: Team,
+        health: u32,
+    },
+    TurnNumberChanged(u32),
+    EnergyChanged(Option<Energy>),
+    RulesChanged(MatchRules),
+    // the damage a card landed during combat, the board changes come as their own deltas
+    Attacked {
+        slot: CardSlot,
+        card: String,
+        damage: u32,
+    },
+    Log(String),
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct Versioned<T> {
+    pub version: u32,
+    pub message: T,
+}
+
+#[derive(Deserialize)]
+struct VersionOnly {
+    version: u32,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ProtocolError {
+    VersionMismatch { expected: u32, found: u32 },
+    Malformed(String),
+    UnknownCard(String),
+}
+
+impl fmt::Display for ProtocolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProtocolError::VersionMismatch { expected, found } => write!(
+                f,
+                "protocol version {} is not supported, expected {}",
+                found, expected
+            ),
+            ProtocolError::Malformed(x) => write!(f, "malformed message: {}", x),
+            ProtocolError::UnknownCard(x) => write!(f, "unknown card {}", x),
+        }
+    }
+}
+
+fn check_version(found: u32) -> Result<(), ProtocolError> {
+    match found == PROTOCOL_VERSION {
+        true => Ok(()),
+        false => Err(ProtocolError::VersionMismatch {
+            expected: PROTOCOL_VERSION,
+            found,
+        }),
+    }
+}
+
+pub fn encode_binary<T: Serialize>(message: &T) -> Vec<u8> {
+    bincode::serialize(&Versioned {
+        version: PROTOCOL_VERSION,
+        message,
+    })
+    .expect("protocol messages always serialize")
+}
+
+pub fn decode_binary<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, ProtocolError> {
+    // the version is read on its own first so newer messages fail with a clear error
+    let version = bincode::deserialize::<u32>(bytes)
+        .map_err(|err| ProtocolError::Malformed(err.to_string()))?;
+    check_version(version)?;
+    bincode::deserialize::<Versioned<T>>(bytes)
+        .map(|x| x.message)
+        .map_err(|err| ProtocolError::Malformed(err.to_string()))
+}
+
+pub fn encode_ron<T: Serialize>(message: &T) -> String {
+    ron::ser::to_string(&Versioned {
+        version: PROTOCOL_VERSION,
+        message,
+    })
+    .expect("protocol messages always serialize")
+}
+
+pub fn decode_ron<T: DeserializeOwned>(text: &str) -> Result<T, ProtocolError> {
+    let version = ron::de::from_str::<VersionOnly>(text)
+        .map_err(|err| ProtocolError::Malformed(err.to_string()))?;
+    check_version(version.version)?;
+    ron::de::from_str::<Versioned<T>>(text)
+        .map(|x| x.message)
+        .map_err(|err| ProtocolError::Malformed(err.to_string()))
+}
+
+impl GameController {
+    // the deltas that rebuild the current board on an empty controller
+    pub fn snapshot(&self, cards: &Assets<Card>) -> Vec<StateDelta> {
+        let mut deltas = vec![StateDelta::RulesChanged(self.get_rules().clone())];
+        for team in [Team::Red, Team::Blue] {
+            for slot_type in [CardSlotType::Hand, CardSlotType::Play] {
+                for id in 0..CARD_SLOT_COUNT {
+                    let slot = CardSlot {
+                        id,
+                        team,
+                        slot_type,
+                    };
+                    let card = self.get_card(&slot).map(|(card, stats)| PlacedCard {
+                        name: cards.get(card).unwrap().name.clone(),
+                        stats,
+                    });
+                    deltas.push(StateDelta::SlotChanged { slot, card });
+                }
+            }
+            deltas.push(StateDelta::TeamHealthChanged {
+                team,
+                health: self.get_team_health(team),
+            });
+            deltas.push(StateDelta::NextDrawChanged {
+                team,
+                card_types: self.get_next_card_types(team).to_vec(),
+            });
+        }
+        deltas.push(StateDelta::TurnChanged {
+            current_team: self.get_current_team(),
+            turn_state: self.get_turn_state(),
+        });
+        deltas.push(StateDelta::TurnNumberChanged(self.get_turn()));
+        deltas.push(StateDelta::EnergyChanged(self.get_energy()));
+        deltas
+    }
+
+    pub fn apply_delta(&mut self, delta: StateDelta) -> Result<(), ProtocolError> {
+        match delta {
+            StateDelta::SlotChanged { slot, card } => match card {
+                Some(PlacedCard { name, stats }) => {
+                    let card = self
+                        .find_card(&name)
+                        .ok_or(ProtocolError::UnknownCard(name))?;
+                    self.push_card_at(slot, card, stats);
+                }
+                None => self.remove_card(slot),
+            },
+            StateDelta::TurnChanged {
+                current_team,
+                turn_state,
+            } => self.set_turn(current_team, turn_state),
+            StateDelta::NextDrawChanged { team, card_types } => {
+                self.set_next_card_types(team, card_types)
+            }
+            StateDelta::TeamHealthChanged { team, health } => self.set_team_health(team, health),
+            StateDelta::TurnNumberChanged(turn) => self.set_turn_number(turn),
+            StateDelta::EnergyChanged(energy) => self.set_energy(energy),
+            StateDelta::RulesChanged(rules) => self.set_rules(rules),
+            StateDelta::Attacked { slot, card, damage } => self.report_attack(slot, card, damage),
+            StateDelta::Log(entry) => self.log(entry),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_state::{Buff, Status, StatusEffect};
+
+    fn get_actions() -> Vec<PlayerAction> {
+        vec![
+            PlayerAction::Mulligan {
+                hand_slots: vec![0, 2],
+            },
+            PlayerAction::Draw { pile: 1 },
+            PlayerAction::Discard { hand_slot: 3 },
+            PlayerAction::Place {
+                hand_slot: 1,
+                play_slot: 4,
+            },
+            PlayerAction::Undo,
+            PlayerAction::EndTurn,
+        ]
+    }
+
+    fn get_deltas() -> Vec<StateDelta> {
+        let slot = CardSlot {
+            id: 2,
+            team: Team::Blue,
+            slot_type: CardSlotType::Play,
+        };
+        vec![
+            StateDelta::SlotChanged {
+                slot: slot.clone(),
+                card: Some(PlacedCard {
+                    name: "Red Knight".to_string(),
+                    stats: CardStats {
+                        hp: Some(3),
+                        max_hp: Some(5),
+                        just_played: true,
+                        equipment: vec!["Sword".to_string()],
+                        buffs: vec![Buff {
+                            damage: 1,
+                            hp: 2,
+                            turns: Some(1),
+                        }],
+                        statuses: vec![Status {
+                            effect: StatusEffect::Poisoned(2),
+                            turns: None,
+                        }],
+                    },
+                }),
+            },
+            StateDelta::SlotChanged {
+                slot: slot.clone(),
+                card: None,
+            },
+            StateDelta::TurnChanged {
+                current_team: Team::Red,
+                turn_state: TurnState::PlayCards,
+            },
+            StateDelta::NextDrawChanged {
+                team: Team::Red,
+                card_types: vec![CardType::Hero, CardType::Equipment],
+            },
+            StateDelta::TeamHealthChanged {
+                team: Team::Blue,
+                health: 12,
+            },
+            StateDelta::TurnNumberChanged(7),
+            StateDelta::EnergyChanged(Some(Energy { current: 1, max: 3 })),
+            StateDelta::EnergyChanged(None),
+            StateDelta::RulesChanged(MatchRules::default()),
+            StateDelta::Attacked {
+                slot,
+                card: "Red Knight".to_string(),
+                damage: 4,
+            },
+            StateDelta::Log("hello".to_string()),
+        ]
+    }
+
+    #[test]
+    fn binary_round_trip() {
+        for action in get_actions() {
+            assert_eq!(
+                decode_binary::<PlayerAction>(&encode_binary(&action)),
+                Ok(action)
+            );
+        }
+        for delta in get_deltas() {
+            assert_eq!(
+                decode_binary::<StateDelta>(&encode_binary(&delta)),
+                Ok(delta)
+            );
+        }
+    }
+
+    #[test]
+    fn ron_round_trip() {
+        for action in get_actions() {
+            assert_eq!(decode_ron::<PlayerAction>(&encode_ron(&action)), Ok(action));
+        }
+        for delta in get_deltas() {
+            assert_eq!(decode_ron::<StateDelta>(&encode_ron(&delta)), Ok(delta));
+        }
+    }
+
+    #[test]
+    fn wrong_version_is_rejected() {
+        let found = PROTOCOL_VERSION + 1;
+        let expected = Err(ProtocolError::VersionMismatch {
+            expected: PROTOCOL_VERSION,
+            found,
+        });
+        let message = Versioned {
+            version: found,
+            message: PlayerAction::EndTurn,
+        };
+        let bytes = bincode::serialize(&message).unwrap();
+        assert_eq!(decode_binary::<PlayerAction>(&bytes), expected);
+        let text = ron::ser::to_string(&message).unwrap();
+        assert_eq!(decode_ron::<PlayerAction>(&text), expected);
+    }
+}
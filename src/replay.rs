@@ -0,0 +1,388 @@
+use crate::assets::LoadState;
+use crate::cards::Card;
+use crate::game_state::{ActionApplied, Team};
+use crate::game_ui_controller::GameController;
+use crate::launch_options::LaunchOptions;
+use crate::match_log::MatchLog;
+use crate::protocol::{decode_ron, PlayerAction, Versioned, PROTOCOL_VERSION};
+use crate::rules::{MatchRules, MatchSeed};
+use bevy::asset::ron;
+use bevy::prelude::*;
+use bevy_rand::prelude::WyRand;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const STEP_SECONDS: f32 = 1.0;
+const SPEEDS: [f32; 4] = [0.5, 1.0, 2.0, 4.0];
+const PLAY_PAUSE_KEY: KeyCode = KeyCode::KeyP;
+const STEP_BACK_KEY: KeyCode = KeyCode::Comma;
+const STEP_FORWARD_KEY: KeyCode = KeyCode::Period;
+const SPEED_KEY: KeyCode = KeyCode::KeyX;
+const BUTTON_COLOR: Color = Color::rgba(1.0, 1.0, 1.0, 0.08);
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct RecordedAction {
+    pub team: Team,
+    pub action: PlayerAction,
+    pub turn: u32,
+    // time into the turn when the action was applied
+    pub elapsed_ms: u32,
+}
+
+// everything needed to play a match again, the seed drives the same draws in the same order
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct Replay {
+    pub seed: u64,
+    pub rules: MatchRules,
+    pub actions: Vec<RecordedAction>,
+}
+
+impl Replay {
+    pub fn get_rng(&self) -> WyRand {
+        WyRand::from_seed(self.seed.to_le_bytes())
+    }
+}
+
+pub fn load_replay(path: &Path) -> Result<Replay, String> {
+    let text = std::fs::read_to_string(path).map_err(|err| err.to_string())?;
+    decode_ron::<Replay>(&text).map_err(|err| err.to_string())
+}
+
+pub fn save_replay(path: &Path, replay: &Replay) {
+    let versioned = Versioned {
+        version: PROTOCOL_VERSION,
+        message: replay,
+    };
+    match ron::ser::to_string_pretty(&versioned, ron::ser::PrettyConfig::default()) {
+        Ok(x) => match std::fs::write(path, x) {
+            Ok(_) => {}
+            Err(err) => warn!("failed to write {}: {}", path.display(), err),
+        },
+        Err(err) => warn!("failed to serialize replay: {}", err),
+    }
+}
+
+#[derive(Resource, Clone, Debug)]
+pub struct ReplayRecorder {
+    path: PathBuf,
+    replay: Replay,
+}
+
+#[derive(Resource, Clone, Debug)]
+pub struct ReplayViewer {
+    replay: Replay,
+    rng: WyRand,
+    step: usize,
+    playing: bool,
+    speed_index: usize,
+    timer: Timer,
+}
+
+impl ReplayViewer {
+    fn new(replay: Replay) -> Self {
+        ReplayViewer {
+            rng: replay.get_rng(),
+            replay,
+            step: 0,
+            playing: false,
+            speed_index: 1,
+            timer: Timer::from_seconds(STEP_SECONDS, TimerMode::Repeating),
+        }
+    }
+
+    pub fn start_board(&mut self, cards: &Assets<Card>) -> GameController {
+        self.rng = self.replay.get_rng();
+        self.step = 0;
+        GameController::new(cards, &self.replay.rules, &mut self.rng)
+    }
+
+    fn step_forward(&mut self, game_ui_controller: &mut GameController, cards: &Assets<Card>) {
+        let RecordedAction { team, action, .. } = match self.replay.actions.get(self.step) {
+            Some(x) => x.clone(),
+            None => {
+                self.playing = false;
+                return;
+            }
+        };
+        match game_ui_controller.apply_action(team, &action, cards, &mut self.rng) {
+            Ok(_) => {}
+            Err(err) => warn!("replay step {} was rejected: {}", self.step, err),
+        }
+        game_ui_controller.resolve_moves(cards, &mut self.rng);
+        self.step += 1;
+    }
+
+    // going back rebuilds the board from the seed since moves can not be undone
+    fn step_back(
+        &mut self,
+        game_ui_controller: &mut GameController,
+        cards: &Assets<Card>,
+        match_log: &mut MatchLog,
+    ) {
+        let target = self.step.saturating_sub(1);
+        *game_ui_controller = self.start_board(cards);
+        game_ui_controller.refresh_board();
+        match_log.clear();
+        while self.step < target {
+            self.step_forward(game_ui_controller, cards);
+        }
+    }
+}
+
+#[derive(Component, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Reflect)]
+pub enum ReplayButton {
+    StepBack,
+    PlayPause,
+    StepForward,
+    Speed,
+}
+
+#[derive(Component, Clone, PartialEq, Eq, PartialOrd, Ord, Reflect)]
+pub struct ReplayStatusMarker;
+
+fn start_replay(
+    mut commands: Commands,
+    launch_options: Res<LaunchOptions>,
+    seed: Res<MatchSeed>,
+    rules: Res<MatchRules>,
+) {
+    match (&launch_options.replay, &launch_options.record) {
+        (Some(path), _) => match load_replay(path) {
+            Ok(replay) => commands.insert_resource(ReplayViewer::new(replay)),
+            Err(err) => error!("failed to load replay {}: {}", path.display(), err),
+        },
+        // a joining process only mirrors the host, so it has nothing of its own to record
+        (None, Some(path)) if !launch_options.is_network_client() => {
+            commands.insert_resource(ReplayRecorder {
+                path: path.clone(),
+                replay: Replay {
+                    seed: seed.0,
+                    rules: rules.clone(),
+                    actions: vec![],
+                },
+            })
+        }
+        _ => {}
+    }
+}
+
+fn record_actions(
+    mut applied: EventReader<ActionApplied>,
+    recorder: Option<ResMut<ReplayRecorder>>,
+) {
+    let mut recorder = match recorder {
+        Some(x) => x,
+        None => {
+            applied.clear();
+            return;
+        }
+    };
+    let mut changed = false;
+    for ActionApplied {
+        team,
+        action,
+        turn,
+        elapsed_ms,
+    } in applied.read()
+    {
+        recorder.replay.actions.push(RecordedAction {
+            team: *team,
+            action: action.clone(),
+            turn: *turn,
+            elapsed_ms: *elapsed_ms,
+        });
+        changed = true;
+    }
+    // written after every action so a crash still leaves a usable replay behind
+    if changed {
+        save_replay(&recorder.path, &recorder.replay);
+    }
+}
+
+fn spawn_replay_controls(
+    mut commands: Commands,
+    assets: Res<AssetServer>,
+    viewer: Option<Res<ReplayViewer>>,
+) {
+    if viewer.is_none() {
+        return;
+    }
+    let text_style = TextStyle {
+        font: assets.load("ui/simple-pixel.ttf"),
+        font_size: 18.0,
+        color: Color::WHITE,
+    };
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                left: Val::Px(0.0),
+                bottom: Val::Px(0.0),
+                padding: UiRect::all(Val::Px(8.0)),
+                column_gap: Val::Px(6.0),
+                align_items: AlignItems::Center,
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+            background_color: BackgroundColor(Color::rgba(0.08, 0.0, 0.07, 0.85)),
+            z_index: ZIndex::Global(2),
+            ..default()
+        })
+        .with_children(|parent| {
+            for button in [
+                ReplayButton::StepBack,
+                ReplayButton::PlayPause,
+                ReplayButton::StepForward,
+                ReplayButton::Speed,
+            ] {
+                parent
+                    .spawn(ButtonBundle {
+                        style: Style {
+                            padding: UiRect::all(Val::Px(4.0)),
+                            ..default()
+                        },
+                        background_color: BackgroundColor(BUTTON_COLOR),
+                        ..default()
+                    })
+                    .insert(button)
+                    .with_children(|parent| {
+                        parent.spawn(TextBundle::from_section("", text_style.clone()));
+                    });
+            }
+            parent
+                .spawn(TextBundle::from_section("", text_style.clone()))
+                .insert(ReplayStatusMarker);
+        });
+}
+
+fn control_replay(
+    keys: Res<ButtonInput<KeyCode>>,
+    interaction_query: Query<(&Interaction, &ReplayButton), Changed<Interaction>>,
+    time: Res<Time>,
+    viewer: Option<ResMut<ReplayViewer>>,
+    mut game_ui_controller_query: Query<&mut GameController>,
+    cards: Res<Assets<Card>>,
+    mut match_log: ResMut<MatchLog>,
+) {
+    let mut viewer = match viewer {
+        Some(x) => x,
+        None => {
+            return;
+        }
+    };
+    let mut game_ui_controller = match game_ui_controller_query.get_single_mut() {
+        Ok(x) => x,
+        _ => {
+            return;
+        }
+    };
+    let mut pressed: Vec<ReplayButton> = interaction_query
+        .iter()
+        .filter(|(interaction, _)| **interaction == Interaction::Pressed)
+        .map(|(_, button)| *button)
+        .collect();
+    for (key, button) in [
+        (STEP_BACK_KEY, ReplayButton::StepBack),
+        (PLAY_PAUSE_KEY, ReplayButton::PlayPause),
+        (STEP_FORWARD_KEY, ReplayButton::StepForward),
+        (SPEED_KEY, ReplayButton::Speed),
+    ] {
+        if keys.just_pressed(key) {
+            pressed.push(button);
+        }
+    }
+    for button in pressed {
+        match button {
+            ReplayButton::StepBack => {
+                viewer.playing = false;
+                viewer.step_back(&mut game_ui_controller, &cards, &mut match_log);
+            }
+            ReplayButton::PlayPause => {
+                viewer.playing = !viewer.playing;
+                viewer.timer.reset();
+            }
+            ReplayButton::StepForward => {
+                viewer.playing = false;
+                viewer.step_forward(&mut game_ui_controller, &cards);
+            }
+            ReplayButton::Speed => viewer.speed_index = (viewer.speed_index + 1) % SPEEDS.len(),
+        }
+    }
+    if !viewer.playing {
+        return;
+    }
+    let speed = SPEEDS[viewer.speed_index];
+    if viewer
+        .timer
+        .tick(time.delta().mul_f32(speed))
+        .just_finished()
+    {
+        viewer.step_forward(&mut game_ui_controller, &cards);
+    }
+}
+
+fn update_replay_controls(
+    viewer: Option<Res<ReplayViewer>>,
+    button_query: Query<(&ReplayButton, &Children)>,
+    mut status_query: Query<&mut Text, With<ReplayStatusMarker>>,
+    mut text_query: Query<&mut Text, Without<ReplayStatusMarker>>,
+) {
+    let viewer = match viewer {
+        Some(x) => x,
+        None => {
+            return;
+        }
+    };
+    // the timer ticks every frame while playing, so only redraw when the labels would differ
+    let status = match viewer
+        .step
+        .checked_sub(1)
+        .map(|x| &viewer.replay.actions[x])
+    {
+        Some(last) => format!(
+            "step {}/{}, turn {} at {:.1}s",
+            viewer.step,
+            viewer.replay.actions.len(),
+            last.turn,
+            last.elapsed_ms as f32 / 1000.0
+        ),
+        None => format!("step 0/{}", viewer.replay.actions.len()),
+    };
+    for mut text in status_query.iter_mut() {
+        if text.sections[0].value != status {
+            text.sections[0].value = status.clone();
+        }
+    }
+    for (button, children) in button_query.iter() {
+        let label = match button {
+            ReplayButton::StepBack => "<".to_string(),
+            ReplayButton::PlayPause => match viewer.playing {
+                true => "pause".to_string(),
+                false => "play".to_string(),
+            },
+            ReplayButton::StepForward => ">".to_string(),
+            ReplayButton::Speed => format!("{}x", SPEEDS[viewer.speed_index]),
+        };
+        for child in children.iter() {
+            match text_query.get_mut(*child) {
+                Ok(mut text) if text.sections[0].value != label => {
+                    text.sections[0].value = label.clone()
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, start_replay)
+            .add_systems(OnEnter(LoadState::Loaded), spawn_replay_controls)
+            .add_systems(
+                Update,
+                (record_actions, control_replay, update_replay_controls).chain(),
+            );
+    }
+}
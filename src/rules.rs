@@ -0,0 +1,1362 @@
+use crate::card_pool::CardPoolError;
+use crate::cards::{Card, CardRarity, CardType, Keyword};
+use crate::color_chains::ChainBonusStats;
+use crate::constants::CARD_SLOT_COUNT;
+use crate::equipment::{can_attach, EquippedCard};
+use crate::game_state::{Buff, CardSlot, CardSlotType, CardStats, StatusEffect, Team, TurnState};
+use crate::game_ui_controller::GameController;
+use crate::protocol::{Energy, PlayerAction};
+use bevy::asset::ron;
+use bevy::prelude::*;
+use num_traits::FromPrimitive;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::fmt;
+use std::path::Path;
+
+// energy granted at the start of each of a team's turns, growing by per_turn up to max
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct EnergyRules {
+    pub starting: u32,
+    pub per_turn: u32,
+    pub max: u32,
+}
+
+impl EnergyRules {
+    pub fn get_energy_for_round(&self, round: u32) -> u32 {
+        self.starting
+            .saturating_add(self.per_turn.saturating_mul(round.saturating_sub(1)))
+            .min(self.max)
+    }
+}
+
+// how often a draw picks each rarity relative to the others, a weight of zero leaves it out
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct RarityWeights {
+    pub common: u32,
+    pub uncommon: u32,
+    pub rare: u32,
+    pub legendary: u32,
+}
+
+impl RarityWeights {
+    pub fn get_weight(&self, rarity: CardRarity) -> u32 {
+        match rarity {
+            CardRarity::Common => self.common,
+            CardRarity::Uncommon => self.uncommon,
+            CardRarity::Rare => self.rare,
+            CardRarity::Legendary => self.legendary,
+        }
+    }
+}
+
+impl Default for RarityWeights {
+    fn default() -> Self {
+        RarityWeights {
+            common: 60,
+            uncommon: 25,
+            rare: 12,
+            legendary: 3,
+        }
+    }
+}
+
+// cards joined by matching colors fight harder, every card of a long enough chain gets the
+// bonus once for each card the chain has past the shortest length that counts
+// the hp bonus is soaked off the damage a card takes in each fight rather than stored
+// a rules file turns it on with Some(()), or with only the fields it changes
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(default)]
+pub struct ChainBonus {
+    pub min_length: usize,
+    pub damage: u32,
+    pub hp: u32,
+}
+
+impl Default for ChainBonus {
+    fn default() -> Self {
+        ChainBonus {
+            min_length: 2,
+            damage: 1,
+            hp: 1,
+        }
+    }
+}
+
+// what placing equipment on a Hero or Beast does
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum EquipmentModel {
+    // both are swapped for the card with the colors of the two
+    #[default]
+    Combine,
+    // the equipment stays on the card and adds its damage, hp and keywords until the card dies
+    Attach,
+}
+
+// how much hp a combined card starts with when the card it replaces was damaged
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum CombinationHp {
+    // it is missing the same amount of hp, but never dies from combining
+    #[default]
+    KeepDamage,
+    // it has the same share of its max hp, rounded up
+    KeepRatio,
+    FullHeal,
+}
+
+impl CombinationHp {
+    pub fn get_hp(&self, replaced: &CardStats, max_hp: u32) -> u32 {
+        let (hp, old_max) = match (replaced.hp, replaced.get_max_hp()) {
+            (Some(hp), Some(old_max)) if old_max > 0 => (hp, old_max),
+            _ => {
+                return max_hp;
+            }
+        };
+        match self {
+            CombinationHp::KeepDamage => max_hp.saturating_sub(old_max.saturating_sub(hp)).max(1),
+            CombinationHp::KeepRatio => (max_hp * hp).div_ceil(old_max).clamp(1, max_hp.max(1)),
+            CombinationHp::FullHeal => max_hp,
+        }
+    }
+}
+
+// what a draw does when no card has the rolled type and color count
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum DrawFallback {
+    // another color count of the same type, or any card when the type has none at all
+    #[default]
+    SameType,
+    AnyCard,
+    // the team draws nothing this turn
+    Skip,
+}
+
+#[derive(Resource, Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct MatchRules {
+    #[serde(default = "default_starting_health")]
+    pub starting_health: u32,
+    #[serde(default = "default_opening_hand_size")]
+    pub opening_hand_size: usize,
+    // without energy rules a team plays exactly one card per turn
+    #[serde(default)]
+    pub energy: Option<EnergyRules>,
+    // a turn that runs longer draws and passes on its own
+    #[serde(default)]
+    pub turn_time_limit_seconds: Option<u32>,
+    // offers two different card types to draw from instead of a single roll
+    #[serde(default)]
+    pub draw_pile_choice: bool,
+    #[serde(default)]
+    pub draw_fallback: DrawFallback,
+    #[serde(default)]
+    pub rarity_weights: RarityWeights,
+    // chain bonuses are off unless a rules file turns them on
+    #[serde(default)]
+    pub chain_bonus: Option<ChainBonus>,
+    #[serde(default)]
+    pub equipment_model: EquipmentModel,
+    #[serde(default)]
+    pub combination_hp: CombinationHp,
+}
+
+fn default_starting_health() -> u32 {
+    100
+}
+
+fn default_opening_hand_size() -> usize {
+    4
+}
+
+impl Default for MatchRules {
+    fn default() -> Self {
+        MatchRules {
+            starting_health: default_starting_health(),
+            opening_hand_size: default_opening_hand_size(),
+            energy: None,
+            turn_time_limit_seconds: None,
+            draw_pile_choice: false,
+            draw_fallback: DrawFallback::default(),
+            rarity_weights: RarityWeights::default(),
+            chain_bonus: None,
+            equipment_model: EquipmentModel::default(),
+            combination_hp: CombinationHp::default(),
+        }
+    }
+}
+
+pub fn load_match_rules(path: &Path) -> MatchRules {
+    match std::fs::read_to_string(path) {
+        Ok(x) => match ron::de::from_str::<MatchRules>(&x) {
+            Ok(rules) => rules,
+            Err(err) => {
+                warn!("failed to parse {}: {}", path.display(), err);
+                MatchRules::default()
+            }
+        },
+        Err(err) => {
+            warn!("failed to read {}: {}", path.display(), err);
+            MatchRules::default()
+        }
+    }
+}
+
+// the seed of the global rng, kept so a match can be replayed
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct MatchSeed(pub u64);
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ActionError {
+    NotYourTurn,
+    WrongPhase(TurnState),
+    NoCardInSlot,
+    IllegalPlacement,
+    AlreadyPlayed,
+    NotEnoughEnergy { cost: u32, available: u32 },
+    NothingToUndo,
+    NoSuchPile(usize),
+    HandFull,
+    HandNotFull,
+    CardPool(CardPoolError),
+}
+
+impl fmt::Display for ActionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ActionError::NotYourTurn => write!(f, "it is not your turn"),
+            ActionError::WrongPhase(state) => write!(f, "not allowed during {:?}", state),
+            ActionError::NoCardInSlot => write!(f, "there is no card in that slot"),
+            ActionError::IllegalPlacement => write!(f, "the card can not be placed there"),
+            ActionError::AlreadyPlayed => write!(f, "a card was already played this turn"),
+            ActionError::NotEnoughEnergy { cost, available } => write!(
+                f,
+                "the card costs {} energy but only {} is left",
+                cost, available
+            ),
+            ActionError::NothingToUndo => write!(f, "there is nothing to undo"),
+            ActionError::NoSuchPile(pile) => write!(f, "there is no draw pile {}", pile),
+            ActionError::HandFull => write!(f, "the hand is full, discard a card first"),
+            ActionError::HandNotFull => write!(f, "cards can only be discarded from a full hand"),
+            ActionError::CardPool(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+pub fn cards_can_combine(first_card: &Card, second_card: &Card) -> bool {
+    [CardType::Hero, CardType::Beast].contains(&first_card.card_type)
+        && second_card
+            .colors
+            .iter()
+            .all(|x| !first_card.colors.contains(x))
+        && second_card.card_type == CardType::Equipment
+}
+
+pub fn can_place_card(placed_to_left: Option<&Card>, to_place: &Card) -> bool {
+    match placed_to_left {
+        None => true,
+        Some(x) => x.colors.last().unwrap() == to_place.colors.first().unwrap(),
+    }
+}
+
+// the type of whichever card is the hero or beast, cards that can not combine have none
+fn get_upgraded_card_type(first_card: &Card, second_card: &Card) -> Option<CardType> {
+    [first_card, second_card]
+        .into_iter()
+        .map(|x| x.card_type)
+        .find(|x| [CardType::Hero, CardType::Beast].contains(x))
+}
+
+impl GameController {
+    pub fn apply_action(
+        &mut self,
+        team: Team,
+        action: &PlayerAction,
+        cards: &Assets<Card>,
+        rng: &mut impl Rng,
+    ) -> Result<(), ActionError> {
+        if team != self.get_current_team() {
+            return Err(ActionError::NotYourTurn);
+        }
+        let required_state = match action {
+            PlayerAction::Mulligan { .. } => TurnState::Mulligan,
+            PlayerAction::Draw { .. } | PlayerAction::Discard { .. } => TurnState::DrawCards,
+            PlayerAction::Place { .. } | PlayerAction::Undo | PlayerAction::EndTurn => {
+                TurnState::PlayCards
+            }
+        };
+        if self.get_turn_state() != required_state {
+            return Err(ActionError::WrongPhase(self.get_turn_state()));
+        }
+        match action {
+            PlayerAction::Mulligan { hand_slots } => self.mulligan(team, hand_slots, cards, rng)?,
+            PlayerAction::Draw { pile } => {
+                let card_type = match self.get_next_card_types(team).get(*pile) {
+                    Some(x) => *x,
+                    None => {
+                        return Err(ActionError::NoSuchPile(*pile));
+                    }
+                };
+                if self.card_stack_full(team, CardSlotType::Hand) {
+                    return Err(ActionError::HandFull);
+                }
+                self.draw(team, card_type, cards, rng)
+            }
+            PlayerAction::Discard { hand_slot } => self.discard(team, *hand_slot, cards)?,
+            PlayerAction::Place {
+                hand_slot,
+                play_slot,
+            } => {
+                // placements stay provisional until the turn is ended, so they can be undone
+                if self.get_energy().is_none() && self.can_undo() {
+                    return Err(ActionError::AlreadyPlayed);
+                }
+                self.save_undo_point();
+                match self.place(team, *hand_slot, *play_slot, cards) {
+                    Ok(_) => {}
+                    Err(err) => {
+                        self.discard_undo_point();
+                        return Err(err);
+                    }
+                }
+            }
+            PlayerAction::Undo => {
+                if !self.undo() {
+                    return Err(ActionError::NothingToUndo);
+                }
+                self.log(format!("{:?} took back their last play", team));
+            }
+            PlayerAction::EndTurn => self.set_turn(team, TurnState::ApplyMoves),
+        }
+        Ok(())
+    }
+
+    fn mulligan(
+        &mut self,
+        team: Team,
+        hand_slots: &[usize],
+        cards: &Assets<Card>,
+        rng: &mut impl Rng,
+    ) -> Result<(), ActionError> {
+        let hand_slots: BTreeSet<usize> = hand_slots.iter().copied().collect();
+        let returned: Vec<CardSlot> = hand_slots
+            .iter()
+            .map(|id| CardSlot {
+                id: *id,
+                team,
+                slot_type: CardSlotType::Hand,
+            })
+            .collect();
+        if returned.iter().any(|slot| self.get_card(slot).is_none()) {
+            return Err(ActionError::NoCardInSlot);
+        }
+        // the replacements are picked first so an empty card pool leaves the hand untouched
+        let drawn = returned
+            .iter()
+            .map(|_| self.get_random_card(rng))
+            .collect::<Result<Vec<AssetId<Card>>, CardPoolError>>()
+            .map_err(ActionError::CardPool)?;
+        for slot in returned.iter() {
+            self.remove_card(slot.clone());
+        }
+        self.stack_cards(team, CardSlotType::Hand);
+        for card in drawn {
+            let slot = CardSlot {
+                id: self.get_first_open_slot(team, CardSlotType::Hand).unwrap(),
+                team,
+                slot_type: CardSlotType::Hand,
+            };
+            self.push_card_into_stack(slot, card, CardStats::new(cards.get(card).unwrap()));
+        }
+        self.stack_cards(team, CardSlotType::Hand);
+        self.log(format!("{:?} redrew {} cards", team, returned.len()));
+        // the starting team chooses first, the first draw follows once both teams have chosen
+        match team == Team::default() {
+            true => self.set_turn(!team, TurnState::Mulligan),
+            false => self.set_turn(!team, TurnState::DrawCards),
+        }
+        Ok(())
+    }
+
+    // rolls the card types offered to the team's next draw, the piles never repeat a type
+    pub fn roll_next_card_types(&mut self, team: Team, rng: &mut impl Rng) {
+        let first = rng.gen_range(0..4);
+        let mut card_types = vec![CardType::from_i8(first).unwrap()];
+        if self.get_rules().draw_pile_choice {
+            let second = (first + rng.gen_range(1..4)) % 4;
+            card_types.push(CardType::from_i8(second).unwrap());
+        }
+        self.set_next_card_types(team, card_types);
+    }
+
+    // a card of the type and color count, or whatever the fallback rule picks instead
+    fn pick_card_to_draw(
+        &self,
+        card_type: CardType,
+        color_count: usize,
+        rng: &mut impl Rng,
+    ) -> Result<AssetId<Card>, CardPoolError> {
+        let err = match self.get_random_card_of_type_with_len(rng, card_type, color_count) {
+            Ok(x) => return Ok(x),
+            Err(err) => err,
+        };
+        match self.get_rules().draw_fallback {
+            DrawFallback::SameType => match self.get_drawable_color_counts(card_type).as_slice() {
+                [] => self.get_random_card(rng),
+                color_counts => {
+                    let color_count = color_counts[rng.gen_range(0..color_counts.len())];
+                    self.get_random_card_of_type_with_len(rng, card_type, color_count)
+                }
+            },
+            DrawFallback::AnyCard => self.get_random_card(rng),
+            DrawFallback::Skip => Err(err),
+        }
+    }
+
+    fn draw(&mut self, team: Team, card_type: CardType, cards: &Assets<Card>, rng: &mut impl Rng) {
+        let card_color_count = rng.gen_range(1..3);
+        let random_card_of_type = match self.pick_card_to_draw(card_type, card_color_count, rng) {
+            Ok(x) => x,
+            Err(err) => {
+                self.log(format!("{:?} drew nothing, {}", team, err));
+                self.roll_next_card_types(team, rng);
+                self.set_turn(team, TurnState::PlayCards);
+                return;
+            }
+        };
+        let random_card_asset = cards.get(random_card_of_type).unwrap();
+        // a full hand is rejected before drawing, so there is always room here
+        let open_slot = self.get_first_open_slot(team, CardSlotType::Hand).unwrap();
+        self.push_card_into_stack(
+            CardSlot {
+                id: open_slot,
+                team,
+                slot_type: CardSlotType::Hand,
+            },
+            random_card_of_type,
+            CardStats::new(random_card_asset),
+        );
+        self.stack_cards(team, CardSlotType::Hand);
+        self.log(format!("{:?} drew {}", team, random_card_asset.name));
+        self.roll_next_card_types(team, rng);
+        self.set_turn(team, TurnState::PlayCards);
+    }
+
+    fn discard(
+        &mut self,
+        team: Team,
+        hand_slot: usize,
+        cards: &Assets<Card>,
+    ) -> Result<(), ActionError> {
+        if !self.card_stack_full(team, CardSlotType::Hand) {
+            return Err(ActionError::HandNotFull);
+        }
+        let slot = CardSlot {
+            id: hand_slot,
+            team,
+            slot_type: CardSlotType::Hand,
+        };
+        let (card, _) = self.get_card(&slot).ok_or(ActionError::NoCardInSlot)?;
+        self.log(format!(
+            "{:?} discarded {}",
+            team,
+            cards.get(card).unwrap().name
+        ));
+        self.remove_card(slot);
+        self.stack_cards(team, CardSlotType::Hand);
+        Ok(())
+    }
+
+    fn place(
+        &mut self,
+        team: Team,
+        hand_slot: usize,
+        play_slot: usize,
+        cards: &Assets<Card>,
+    ) -> Result<(), ActionError> {
+        if play_slot >= CARD_SLOT_COUNT {
+            return Err(ActionError::IllegalPlacement);
+        }
+        let from = CardSlot {
+            id: hand_slot,
+            team,
+            slot_type: CardSlotType::Hand,
+        };
+        let (card, stats) = self.get_card(&from).ok_or(ActionError::NoCardInSlot)?;
+        let held_card = cards.get(card).unwrap();
+        let remaining_energy = match self.get_energy() {
+            Some(energy) => match energy.current.checked_sub(held_card.get_cost()) {
+                Some(current) => Some(Energy { current, ..energy }),
+                None => {
+                    return Err(ActionError::NotEnoughEnergy {
+                        cost: held_card.get_cost(),
+                        available: energy.current,
+                    })
+                }
+            },
+            None => None,
+        };
+        let to = CardSlot {
+            id: play_slot,
+            team,
+            slot_type: CardSlotType::Play,
+        };
+
+        // combining an equipment with a creature replaces the creature with the upgraded card
+        match self.get_card(&to) {
+            Some(x) if self.get_rules().equipment_model == EquipmentModel::Attach => {
+                let slot_card = cards.get(x.0).unwrap();
+                // either can be the one placed, the creature keeps whatever damage it has taken
+                let attach = match (
+                    can_attach(slot_card, held_card),
+                    can_attach(held_card, slot_card),
+                ) {
+                    (true, _) => Some((x.0, x.1, held_card)),
+                    (_, true) => Some((
+                        card,
+                        CardStats {
+                            just_played: true,
+                            ..stats.clone()
+                        },
+                        slot_card,
+                    )),
+                    _ => None,
+                };
+                if let Some((host, host_stats, equipment)) = attach {
+                    let mut attached = host_stats.equipment.clone();
+                    attached.push(equipment.name.clone());
+                    self.log(format!(
+                        "{:?} equipped {} with {}",
+                        team,
+                        cards.get(host).unwrap().name,
+                        equipment.name
+                    ));
+                    let extra_hp = equipment.hp.unwrap_or(0);
+                    let host_stats = CardStats {
+                        hp: host_stats.hp.map(|hp| hp + extra_hp),
+                        max_hp: host_stats.get_max_hp().map(|hp| hp + extra_hp),
+                        equipment: attached,
+                        ..host_stats
+                    };
+                    self.remove_card(from);
+                    self.push_card_at(to, host, host_stats);
+                    self.set_energy(remaining_energy);
+                    self.stack_cards(team, CardSlotType::Hand);
+                    return Ok(());
+                }
+            }
+            Some(x) => {
+                let slot_card = cards.get(x.0).unwrap();
+                if cards_can_combine(slot_card, held_card)
+                    || cards_can_combine(held_card, slot_card)
+                {
+                    let mut colors = slot_card.colors.clone();
+                    colors.extend(held_card.colors.clone());
+                    let card_type = get_upgraded_card_type(slot_card, held_card)
+                        .ok_or(ActionError::IllegalPlacement)?;
+                    let (combined, combined_id) = self
+                        .get_card_with_colors(colors, cards, card_type)
+                        .ok_or(ActionError::IllegalPlacement)?;
+                    self.log(format!(
+                        "{:?} combined {} with {} into {}",
+                        team, held_card.name, slot_card.name, combined.name
+                    ));
+                    // the card on the board is the one that fought, its damage and effects carry over
+                    let max_hp = combined
+                        .hp
+                        .map(|hp| hp + x.1.buffs.iter().map(|x| x.hp).sum::<u32>());
+                    let combined_stats = CardStats {
+                        hp: max_hp.map(|max| self.get_rules().combination_hp.get_hp(&x.1, max)),
+                        max_hp,
+                        just_played: true,
+                        ..x.1
+                    };
+                    self.remove_card(from);
+                    self.push_card_at(to, combined_id, combined_stats);
+                    self.set_energy(remaining_energy);
+                    self.stack_cards(team, CardSlotType::Hand);
+                    return Ok(());
+                }
+            }
+            None => {}
+        }
+        // attached equipment is never played on its own
+        if self.get_rules().equipment_model == EquipmentModel::Attach
+            && held_card.card_type == CardType::Equipment
+        {
+            return Err(ActionError::IllegalPlacement);
+        }
+
+        // empty targets slide left to the first open slot, so the chain is checked against that slot
+        let first_open = self
+            .get_first_open_slot(team, CardSlotType::Play)
+            .ok_or(ActionError::IllegalPlacement)?;
+        let target = match self.get_card(&to) {
+            Some(_) => to.id,
+            None => to.id.min(first_open),
+        };
+        let left_card = match target {
+            0 => None,
+            _ => self
+                .get_card(&CardSlot {
+                    id: target - 1,
+                    team,
+                    slot_type: CardSlotType::Play,
+                })
+                .map(|x| cards.get(x.0).unwrap()),
+        };
+        if !can_place_card(left_card, held_card) {
+            return Err(ActionError::IllegalPlacement);
+        }
+        self.log(format!("{:?} played {}", team, held_card.name));
+        self.set_energy(remaining_energy);
+        self.remove_card(from);
+        self.push_card_into_stack(
+            CardSlot {
+                id: target,
+                team,
+                slot_type: CardSlotType::Play,
+            },
+            card,
+            CardStats {
+                just_played: true,
+                ..stats
+            },
+        );
+        self.stack_cards(team, CardSlotType::Hand);
+        self.stack_cards(team, CardSlotType::Play);
+        Ok(())
+    }
+
+    pub fn refill_energy(&mut self) {
+        let energy = self.get_rules().energy.as_ref().map(|x| {
+            let max = x.get_energy_for_round(self.get_round());
+            Energy { current: max, max }
+        });
+        self.set_energy(energy);
+    }
+
+    // cards without hp can not be hurt, and no card loses more than it has left
+    pub fn get_landed_damage(&self, slot: &CardSlot, damage: u32) -> u32 {
+        self.get_card(slot)
+            .and_then(|(_, stats)| stats.hp)
+            .map(|hp| hp.min(damage))
+            .unwrap_or(0)
+    }
+
+    // the card a card attacks, None when it hits the other team
+    // a card fights the one across its lane unless it flies over it, with nothing there a Taunt
+    // card in a neighbouring lane pulls the attack
+    fn get_attack_target(
+        &self,
+        attacker_slot: &CardSlot,
+        attacker: &EquippedCard,
+        cards: &Assets<Card>,
+    ) -> Option<CardSlot> {
+        let get_foe_card = |id: usize| {
+            let slot = CardSlot {
+                id,
+                team: !attacker_slot.team,
+                slot_type: CardSlotType::Play,
+            };
+            self.get_equipped_card(&slot, cards)
+                .map(|card| (slot, card))
+        };
+        match get_foe_card(attacker_slot.id) {
+            Some((slot, foe))
+                if !attacker.has_keyword(Keyword::Flying) || foe.has_keyword(Keyword::Flying) =>
+            {
+                return Some(slot);
+            }
+            _ => {}
+        }
+        [attacker_slot.id.checked_sub(1), Some(attacker_slot.id + 1)]
+            .into_iter()
+            .flatten()
+            .filter(|id| *id < CARD_SLOT_COUNT)
+            .filter_map(get_foe_card)
+            .find(|(_, foe)| foe.has_keyword(Keyword::Taunt))
+            .map(|(slot, _)| slot)
+    }
+
+    fn attack(
+        &mut self,
+        attacker_slot: CardSlot,
+        attacker: &EquippedCard,
+        target: Option<CardSlot>,
+        chain_bonuses: &BTreeMap<CardSlot, ChainBonusStats>,
+        cards: &Assets<Card>,
+    ) {
+        if !attacker.stats.can_strike() {
+            self.log(format!("{} could not strike", attacker.card.name));
+            return;
+        }
+        let get_bonus = |slot: &CardSlot| chain_bonuses.get(slot).copied().unwrap_or_default();
+        // cards that deal no damage of their own get nothing from a chain
+        let damage = attacker
+            .get_damage()
+            .map(|x| x + get_bonus(&attacker_slot).damage)
+            .unwrap_or(0);
+        let foe_team = !attacker_slot.team;
+        let (landed_on_card, damage_to_team) = match &target {
+            Some(slot) => {
+                let shield = self.get_card(slot).map(|x| x.1.get_shield()).unwrap_or(0);
+                let damage = damage.saturating_sub(get_bonus(slot).hp + shield);
+                let landed = self.get_landed_damage(slot, damage);
+                self.damage_card(slot, damage);
+                if landed > 0 {
+                    for status in attacker.get_inflicted_statuses() {
+                        self.give_status(slot, status, cards);
+                    }
+                }
+                match attacker.has_keyword(Keyword::Piercing) {
+                    true => (landed, damage - landed),
+                    false => (landed, 0),
+                }
+            }
+            None => (0, damage),
+        };
+        let landed_on_team = damage_to_team.min(self.get_team_health(foe_team));
+        self.set_team_health(foe_team, self.get_team_health(foe_team) - landed_on_team);
+        let landed = landed_on_card + landed_on_team;
+        let team = attacker_slot.team;
+        let health = self.get_team_health(team);
+        let healed = (health + landed).min(self.get_rules().starting_health.max(health));
+        if attacker.has_keyword(Keyword::Lifesteal) && healed > health {
+            self.set_team_health(team, healed);
+            self.log(format!(
+                "{} healed {:?} by {}",
+                attacker.card.name,
+                team,
+                healed - health
+            ));
+        }
+        self.report_attack(attacker_slot, attacker.card.name.clone(), landed);
+    }
+
+    // buffs count down and the hp they gave goes with them, though it never kills the card
+    fn tick_buffs(&mut self, slot: &CardSlot, cards: &Assets<Card>) {
+        let (card, stats) = match self.get_card(slot) {
+            Some(x) if !x.1.buffs.is_empty() => x,
+            _ => {
+                return;
+            }
+        };
+        let mut buffs = vec![];
+        let mut lost_hp = 0;
+        for buff in stats.buffs.iter() {
+            match buff.turns {
+                Some(0 | 1) => {
+                    lost_hp += buff.hp;
+                }
+                Some(turns) => buffs.push(Buff {
+                    turns: Some(turns - 1),
+                    ..*buff
+                }),
+                None => buffs.push(*buff),
+            }
+        }
+        if lost_hp > 0 {
+            self.log(format!(
+                "a buff on {} wore off",
+                cards.get(card).unwrap().name
+            ));
+        }
+        let max_hp = stats.get_max_hp().map(|x| x.saturating_sub(lost_hp).max(1));
+        self.push_card_at(
+            slot.clone(),
+            card,
+            CardStats {
+                hp: stats.hp.zip(max_hp).map(|(hp, max)| hp.min(max)),
+                max_hp,
+                buffs,
+                ..stats
+            },
+        );
+    }
+
+    fn regenerate(&mut self, slot: &CardSlot, cards: &Assets<Card>) {
+        let (card, stats) = match self.get_card(slot) {
+            Some(x) if !x.1.has_status(StatusEffect::Frozen) => x,
+            _ => {
+                return;
+            }
+        };
+        let equipped = self.equip(cards.get(card).unwrap(), &stats, cards);
+        let hp = match (stats.hp, stats.get_max_hp()) {
+            (Some(hp), Some(max)) => (hp + equipped.get_regeneration()).min(max.max(hp)),
+            _ => {
+                return;
+            }
+        };
+        if Some(hp) != stats.hp {
+            self.push_card_at(
+                slot.clone(),
+                card,
+                CardStats {
+                    hp: Some(hp),
+                    ..stats
+                },
+            );
+        }
+    }
+
+    // poison, burning, Regenerate and buffs act on every card left standing once the fighting
+    // is over
+    fn apply_end_of_turn_keywords(&mut self, cards: &Assets<Card>) {
+        for team in [self.get_current_team(), !self.get_current_team()] {
+            for id in 0..CARD_SLOT_COUNT {
+                let slot = CardSlot {
+                    id,
+                    team,
+                    slot_type: CardSlotType::Play,
+                };
+                let hp_loss = match self.get_card(&slot) {
+                    Some((_, stats)) => stats.get_hp_loss(),
+                    None => {
+                        continue;
+                    }
+                };
+                if hp_loss > 0 {
+                    self.damage_card(&slot, hp_loss);
+                }
+                self.regenerate(&slot, cards);
+                self.tick_buffs(&slot, cards);
+            }
+        }
+    }
+
+    // the current team's play row fights the opposing row lane by lane
+    // both cards of a lane strike at once, so a card that dies still deals its damage
+    // triggers resolve at fixed points, on play ones before the fighting, deaths after it and
+    // start of turn ones once the other team takes over and its statuses have counted down
+    pub fn resolve_moves(&mut self, cards: &Assets<Card>, rng: &mut impl Rng) {
+        if self.get_turn_state() != TurnState::ApplyMoves {
+            return;
+        }
+        let team = self.get_current_team();
+        self.clear_undo_points();
+        let mut queue = VecDeque::new();
+        self.queue_play_triggers(&mut queue, team, cards);
+        self.resolve_effects(queue, cards, rng);
+        let chain_bonuses = self.get_chain_bonuses(cards);
+        // every lane picks its attack from the board before combat, so a card killed by a
+        // redirected attack in an earlier lane still gets its own strike
+        let attacks: Vec<(CardSlot, EquippedCard, Option<CardSlot>)> = (0..CARD_SLOT_COUNT)
+            .flat_map(|id| {
+                [team, !team].into_iter().map(move |team| CardSlot {
+                    id,
+                    team,
+                    slot_type: CardSlotType::Play,
+                })
+            })
+            .filter_map(|slot| {
+                let card = self.get_equipped_card(&slot, cards)?;
+                let target = self.get_attack_target(&slot, &card, cards);
+                Some((slot, card, target))
+            })
+            .collect();
+        for (slot, card, target) in attacks {
+            self.attack(slot, &card, target, &chain_bonuses, cards);
+        }
+        self.apply_end_of_turn_keywords(cards);
+        self.resolve_effects(VecDeque::new(), cards, rng);
+        self.stack_cards(Team::Red, CardSlotType::Play);
+        self.stack_cards(Team::Blue, CardSlotType::Play);
+        self.log(format!("{:?} ended their turn", team));
+        self.set_turn(!team, TurnState::DrawCards);
+        self.advance_turn();
+        self.refill_energy();
+        self.tick_statuses(!team, cards);
+        let mut queue = VecDeque::new();
+        self.queue_turn_start_triggers(&mut queue, !team, cards);
+        self.resolve_effects(queue, cards, rng);
+        self.stack_cards(Team::Red, CardSlotType::Play);
+        self.stack_cards(Team::Blue, CardSlotType::Play);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_state::Status;
+    use crate::protocol::StateDelta;
+    use bevy_rand::prelude::WyRand;
+    use rand::SeedableRng;
+
+    struct Board {
+        cards: Assets<Card>,
+        gc: GameController,
+    }
+
+    fn play_slot(team: Team, id: usize) -> CardSlot {
+        CardSlot {
+            id,
+            team,
+            slot_type: CardSlotType::Play,
+        }
+    }
+
+    fn damaged(hp: u32, max_hp: Option<u32>) -> CardStats {
+        CardStats {
+            hp: Some(hp),
+            max_hp,
+            ..default()
+        }
+    }
+
+    #[test]
+    fn combination_keeps_damage() {
+        let hp = CombinationHp::KeepDamage;
+        assert_eq!(hp.get_hp(&damaged(2, Some(5)), 7), 4);
+        // combining never kills the card
+        assert_eq!(hp.get_hp(&damaged(1, Some(5)), 3), 1);
+    }
+
+    #[test]
+    fn combination_keeps_ratio_rounded_up() {
+        let hp = CombinationHp::KeepRatio;
+        assert_eq!(hp.get_hp(&damaged(2, Some(5)), 7), 3);
+        assert_eq!(hp.get_hp(&damaged(1, Some(3)), 4), 2);
+        assert_eq!(hp.get_hp(&damaged(5, Some(5)), 8), 8);
+    }
+
+    #[test]
+    fn combination_full_heal() {
+        assert_eq!(CombinationHp::FullHeal.get_hp(&damaged(1, Some(5)), 7), 7);
+    }
+
+    #[test]
+    fn combination_without_max_hp_counts_hp_as_full() {
+        for hp in [
+            CombinationHp::KeepDamage,
+            CombinationHp::KeepRatio,
+            CombinationHp::FullHeal,
+        ] {
+            assert_eq!(hp.get_hp(&damaged(3, None), 7), 7);
+            assert_eq!(hp.get_hp(&CardStats::default(), 7), 7);
+        }
+    }
+
+    fn hand_slot(team: Team, id: usize) -> CardSlot {
+        CardSlot {
+            id,
+            team,
+            slot_type: CardSlotType::Hand,
+        }
+    }
+
+    impl Board {
+        // single color heroes as (team, lane, damage, hp, keywords), neighbouring lanes get
+        // different colors so no chain forms
+        fn new(placed: &[(Team, usize, u32, u32, &str)]) -> Self {
+            let colors = ["Red", "Blue", "Green", "Yellow", "Purple"];
+            let placed: Vec<(Option<CardSlot>, String)> = placed
+                .iter()
+                .map(|(team, id, damage, hp, keywords)| {
+                    let fields = format!(
+                        "name: \"{:?} {}\", colors: [{}], card_type: Hero, damage: Some({}), hp: Some({}), keywords: [{}]",
+                        team, id, colors[*id], damage, hp, keywords
+                    );
+                    (Some(play_slot(*team, *id)), fields)
+                })
+                .collect();
+            Board::with_cards(&placed)
+        }
+
+        // cards as the fields of their ron files, the ones without a slot are only there for
+        // combining to find
+        fn with_cards(placed: &[(Option<CardSlot>, String)]) -> Self {
+            Board::with_rules(&MatchRules::default(), placed)
+        }
+
+        fn with_rules(rules: &MatchRules, placed: &[(Option<CardSlot>, String)]) -> Self {
+            let mut cards = Assets::<Card>::default();
+            let mut board = vec![];
+            for (slot, fields) in placed {
+                let card: Card =
+                    ron::de::from_str(&format!("Card(text: \"\", image: \"\", {})", fields))
+                        .unwrap();
+                let stats = CardStats::new(&card);
+                let card = cards.add(card).id();
+                if let Some(slot) = slot {
+                    board.push((slot.clone(), card, stats));
+                }
+            }
+            let mut gc = GameController::empty(&cards, rules);
+            for (slot, card, stats) in board {
+                gc.push_card_at(slot, card, stats);
+            }
+            Board { cards, gc }
+        }
+
+        fn place(
+            &mut self,
+            team: Team,
+            hand_slot: usize,
+            play_slot: usize,
+        ) -> Result<(), ActionError> {
+            self.gc.set_turn(team, TurnState::PlayCards);
+            self.gc.apply_action(
+                team,
+                &PlayerAction::Place {
+                    hand_slot,
+                    play_slot,
+                },
+                &self.cards,
+                &mut WyRand::seed_from_u64(0),
+            )
+        }
+
+        fn resolve(&mut self, team: Team) {
+            self.gc.set_turn(team, TurnState::ApplyMoves);
+            self.gc
+                .resolve_moves(&self.cards, &mut WyRand::seed_from_u64(0));
+        }
+
+        fn get_stats(&self, slot: &CardSlot) -> Option<CardStats> {
+            self.gc.get_card(slot).map(|x| x.1)
+        }
+    }
+
+    #[test]
+    fn taunt_redirects_unblocked_attacks() {
+        let mut board = Board::new(&[(Team::Red, 0, 2, 5, ""), (Team::Blue, 1, 0, 5, "Taunt")]);
+        let health = board.gc.get_team_health(Team::Blue);
+        board.resolve(Team::Red);
+        // the play row slides left once combat is over
+        let taunt = board.get_stats(&play_slot(Team::Blue, 0)).unwrap();
+        assert_eq!(taunt.hp, Some(3));
+        assert_eq!(board.gc.get_team_health(Team::Blue), health);
+    }
+
+    #[test]
+    fn every_lane_strikes_before_cards_die() {
+        let mut board = Board::new(&[
+            (Team::Red, 0, 5, 5, ""),
+            (Team::Red, 1, 0, 5, ""),
+            (Team::Blue, 1, 3, 1, "Taunt"),
+        ]);
+        board.resolve(Team::Red);
+        assert_eq!(board.get_stats(&play_slot(Team::Blue, 1)), None);
+        let target = board.get_stats(&play_slot(Team::Red, 1)).unwrap();
+        assert_eq!(target.hp, Some(2));
+    }
+
+    #[test]
+    fn flying_skips_blockers_without_flying() {
+        let mut board = Board::new(&[(Team::Red, 0, 2, 5, "Flying"), (Team::Blue, 0, 0, 5, "")]);
+        let health = board.gc.get_team_health(Team::Blue);
+        board.resolve(Team::Red);
+        let blocker = board.get_stats(&play_slot(Team::Blue, 0)).unwrap();
+        assert_eq!(blocker.hp, Some(5));
+        assert_eq!(board.gc.get_team_health(Team::Blue), health - 2);
+    }
+
+    #[test]
+    fn piercing_carries_damage_to_the_team() {
+        let mut board = Board::new(&[(Team::Red, 0, 5, 5, "Piercing"), (Team::Blue, 0, 0, 2, "")]);
+        let health = board.gc.get_team_health(Team::Blue);
+        board.resolve(Team::Red);
+        assert_eq!(board.get_stats(&play_slot(Team::Blue, 0)), None);
+        assert_eq!(board.gc.get_team_health(Team::Blue), health - 3);
+    }
+
+    #[test]
+    fn lifesteal_heals_up_to_the_starting_health() {
+        let mut board = Board::new(&[(Team::Red, 0, 4, 5, "Lifesteal")]);
+        let starting = board.gc.get_rules().starting_health;
+        board.gc.set_team_health(Team::Red, starting - 1);
+        board.resolve(Team::Red);
+        assert_eq!(board.gc.get_team_health(Team::Red), starting);
+        assert_eq!(board.gc.get_team_health(Team::Blue), starting - 4);
+    }
+
+    #[test]
+    fn poison_sticks_and_hurts_at_the_end_of_the_turn() {
+        let mut board = Board::new(&[(Team::Red, 0, 1, 5, "Poison(1)"), (Team::Blue, 0, 0, 5, "")]);
+        board.resolve(Team::Red);
+        let target = board.get_stats(&play_slot(Team::Blue, 0)).unwrap();
+        assert!(target.has_status(StatusEffect::Poisoned(1)));
+        assert_eq!(target.hp, Some(3));
+    }
+
+    #[test]
+    fn timed_status_lasts_through_the_owners_turns() {
+        let mut board = Board::new(&[(Team::Blue, 0, 2, 5, "")]);
+        let slot = play_slot(Team::Blue, 0);
+        let stunned = Status {
+            effect: StatusEffect::Stunned,
+            turns: Some(1),
+        };
+        board.gc.give_status(&slot, stunned, &board.cards);
+        let health = board.gc.get_team_health(Team::Red);
+        for team in [Team::Red, Team::Blue, Team::Red] {
+            board.resolve(team);
+            assert_eq!(board.gc.get_team_health(Team::Red), health);
+        }
+        board.resolve(Team::Blue);
+        assert_eq!(board.gc.get_team_health(Team::Red), health - 2);
+        assert!(board.get_stats(&slot).unwrap().statuses.is_empty());
+    }
+
+    #[test]
+    fn regenerate_does_not_heal_frozen_cards() {
+        let mut board = Board::new(&[(Team::Red, 0, 0, 5, "Regenerate(2)")]);
+        let slot = play_slot(Team::Red, 0);
+        let (card, stats) = board.gc.get_card(&slot).unwrap();
+        let frozen = Status {
+            effect: StatusEffect::Frozen,
+            turns: Some(2),
+        };
+        board.gc.push_card_at(
+            slot.clone(),
+            card,
+            CardStats {
+                hp: Some(2),
+                statuses: vec![frozen],
+                ..stats
+            },
+        );
+        board.resolve(Team::Red);
+        assert_eq!(board.get_stats(&slot).unwrap().hp, Some(2));
+    }
+
+    fn hero(colors: &str) -> String {
+        format!(
+            "name: \"{} hero\", colors: [{}], card_type: Hero, damage: Some(1), hp: Some(3)",
+            colors, colors
+        )
+    }
+
+    fn equipment(colors: &str) -> String {
+        format!(
+            "name: \"{} equipment\", colors: [{}], card_type: Equipment, damage: Some(1), hp: Some(1)",
+            colors, colors
+        )
+    }
+
+    #[test]
+    fn combining_uses_up_the_equipment() {
+        let mut board = Board::with_cards(&[
+            (Some(play_slot(Team::Red, 0)), hero("Red")),
+            (Some(hand_slot(Team::Red, 0)), equipment("Blue")),
+            (None, hero("Red, Blue")),
+        ]);
+        board.place(Team::Red, 0, 0).unwrap();
+        let (combined, _) = board.gc.get_card(&play_slot(Team::Red, 0)).unwrap();
+        assert_eq!(board.cards.get(combined).unwrap().name, "Red, Blue hero");
+        assert_eq!(board.gc.get_card(&hand_slot(Team::Red, 0)), None);
+    }
+
+    #[test]
+    fn combining_is_the_turns_play() {
+        let mut board = Board::with_cards(&[
+            (Some(play_slot(Team::Red, 0)), hero("Red")),
+            (Some(hand_slot(Team::Red, 0)), equipment("Blue")),
+            (Some(hand_slot(Team::Red, 1)), hero("Blue")),
+            (None, hero("Red, Blue")),
+        ]);
+        board.place(Team::Red, 0, 0).unwrap();
+        assert_eq!(
+            board.place(Team::Red, 0, 1),
+            Err(ActionError::AlreadyPlayed)
+        );
+    }
+
+    #[test]
+    fn unopposed_cards_hit_the_other_team() {
+        let mut board = Board::new(&[(Team::Red, 0, 3, 5, "")]);
+        let starting = board.gc.get_rules().starting_health;
+        board.resolve(Team::Red);
+        assert_eq!(board.gc.get_team_health(Team::Blue), starting - 3);
+        assert_eq!(board.gc.get_team_health(Team::Red), starting);
+    }
+
+    #[test]
+    fn team_health_stops_at_zero() {
+        let mut board = Board::new(&[(Team::Red, 0, 5, 5, "")]);
+        board.gc.set_team_health(Team::Blue, 2);
+        board.resolve(Team::Red);
+        assert_eq!(board.gc.get_team_health(Team::Blue), 0);
+    }
+
+    #[test]
+    fn placing_into_an_empty_slot_chains_with_the_slot_it_slides_into() {
+        let mut board = Board::with_cards(&[
+            (Some(play_slot(Team::Red, 0)), hero("Red")),
+            (Some(hand_slot(Team::Red, 0)), hero("Blue")),
+            (Some(hand_slot(Team::Red, 1)), hero("Red, Green")),
+        ]);
+        // the empty slot clicked has nothing to its left, the one the card lands in does
+        assert_eq!(
+            board.place(Team::Red, 0, 3),
+            Err(ActionError::IllegalPlacement)
+        );
+        board.place(Team::Red, 1, 3).unwrap();
+        let (placed, _) = board.gc.get_card(&play_slot(Team::Red, 1)).unwrap();
+        assert_eq!(board.cards.get(placed).unwrap().name, "Red, Green hero");
+    }
+
+    fn beast(colors: &str) -> String {
+        format!(
+            "name: \"{} beast\", colors: [{}], card_type: Beast, damage: Some(1), hp: Some(3)",
+            colors, colors
+        )
+    }
+
+    // the names drawn when two color heroes are wanted and there are none
+    fn draw_two_color_heroes(draw_fallback: DrawFallback) -> Vec<Result<String, CardPoolError>> {
+        let rules = MatchRules {
+            draw_fallback,
+            ..default()
+        };
+        let board = Board::with_rules(&rules, &[(None, hero("Red")), (None, beast("Blue"))]);
+        let mut rng = WyRand::seed_from_u64(0);
+        (0..100)
+            .map(|_| {
+                board
+                    .gc
+                    .pick_card_to_draw(CardType::Hero, 2, &mut rng)
+                    .map(|x| board.cards.get(x).unwrap().name.clone())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn same_type_fallback_draws_another_color_count() {
+        let drawn = draw_two_color_heroes(DrawFallback::SameType);
+        assert!(drawn.iter().all(|x| x.as_deref() == Ok("Red hero")));
+    }
+
+    #[test]
+    fn same_type_fallback_draws_any_card_when_the_type_has_none() {
+        let board = Board::with_cards(&[(None, beast("Blue"))]);
+        let drawn = board
+            .gc
+            .pick_card_to_draw(CardType::Hero, 1, &mut WyRand::seed_from_u64(0))
+            .unwrap();
+        assert_eq!(board.cards.get(drawn).unwrap().name, "Blue beast");
+    }
+
+    #[test]
+    fn any_card_fallback_draws_from_every_type() {
+        let drawn = draw_two_color_heroes(DrawFallback::AnyCard);
+        assert!(drawn.iter().any(|x| x.as_deref() == Ok("Red hero")));
+        assert!(drawn.iter().any(|x| x.as_deref() == Ok("Blue beast")));
+    }
+
+    #[test]
+    fn skip_fallback_draws_nothing() {
+        let drawn = draw_two_color_heroes(DrawFallback::Skip);
+        let skipped = Err(CardPoolError::EmptyBucket {
+            card_type: CardType::Hero,
+            color_count: 2,
+        });
+        assert!(drawn.iter().all(|x| *x == skipped));
+    }
+
+    fn attach_board(placed: &[(Option<CardSlot>, String)]) -> Board {
+        let rules = MatchRules {
+            equipment_model: EquipmentModel::Attach,
+            ..default()
+        };
+        Board::with_rules(&rules, placed)
+    }
+
+    #[test]
+    fn attaching_to_an_empty_slot_is_rejected() {
+        let mut board = attach_board(&[
+            (Some(play_slot(Team::Red, 0)), hero("Red")),
+            (Some(hand_slot(Team::Red, 0)), equipment("Blue")),
+        ]);
+        assert_eq!(
+            board.place(Team::Red, 0, 1),
+            Err(ActionError::IllegalPlacement)
+        );
+        assert!(board.gc.get_card(&hand_slot(Team::Red, 0)).is_some());
+    }
+
+    #[test]
+    fn attaching_to_a_card_of_the_same_color_is_rejected() {
+        let mut board = attach_board(&[
+            (Some(play_slot(Team::Red, 0)), hero("Red")),
+            (Some(hand_slot(Team::Red, 0)), equipment("Red")),
+        ]);
+        assert_eq!(
+            board.place(Team::Red, 0, 0),
+            Err(ActionError::IllegalPlacement)
+        );
+        assert!(board
+            .get_stats(&play_slot(Team::Red, 0))
+            .unwrap()
+            .equipment
+            .is_empty());
+    }
+
+    #[test]
+    fn attached_equipment_adds_its_stats_and_keeps_the_damage_taken() {
+        let mut board = attach_board(&[
+            (Some(play_slot(Team::Red, 0)), hero("Red")),
+            (Some(hand_slot(Team::Red, 0)), equipment("Blue")),
+        ]);
+        let slot = play_slot(Team::Red, 0);
+        let (card, stats) = board.gc.get_card(&slot).unwrap();
+        board.gc.push_card_at(
+            slot.clone(),
+            card,
+            CardStats {
+                hp: Some(2),
+                ..stats
+            },
+        );
+        board.place(Team::Red, 0, 0).unwrap();
+        let equipped = board.gc.get_equipped_card(&slot, &board.cards).unwrap();
+        assert_eq!(equipped.card.name, "Red hero");
+        assert_eq!(equipped.equipment.len(), 1);
+        assert_eq!(equipped.get_damage(), Some(2));
+        assert_eq!(equipped.stats.hp, Some(3));
+        assert_eq!(equipped.stats.get_max_hp(), Some(4));
+        assert_eq!(board.gc.get_card(&hand_slot(Team::Red, 0)), None);
+    }
+
+    #[test]
+    fn equipment_leaves_the_board_with_its_carrier() {
+        let mut board = attach_board(&[
+            (Some(play_slot(Team::Red, 0)), hero("Red")),
+            (Some(hand_slot(Team::Red, 0)), equipment("Blue")),
+            (
+                Some(play_slot(Team::Blue, 0)),
+                "name: \"Green giant\", colors: [Green], card_type: Hero, damage: Some(9), hp: Some(9)"
+                    .to_string(),
+            ),
+        ]);
+        board.place(Team::Red, 0, 0).unwrap();
+        board.gc.take_deltas();
+        board.resolve(Team::Blue);
+        assert_eq!(board.get_stats(&play_slot(Team::Red, 0)), None);
+        let discarded = StateDelta::Log("Blue equipment was discarded with it".to_string());
+        assert!(board.gc.take_deltas().contains(&discarded));
+        for team in [Team::Red, Team::Blue] {
+            for id in 0..CARD_SLOT_COUNT {
+                if let Some(stats) = board.get_stats(&play_slot(team, id)) {
+                    assert!(stats.equipment.is_empty());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn chain_bonus_is_opt_in() {
+        assert_eq!(MatchRules::default().chain_bonus, None);
+        let rules: MatchRules = ron::de::from_str("(chain_bonus: Some((damage: 2)))").unwrap();
+        assert_eq!(
+            rules.chain_bonus,
+            Some(ChainBonus {
+                damage: 2,
+                ..default()
+            })
+        );
+        let rules: MatchRules = ron::de::from_str("(chain_bonus: Some(()))").unwrap();
+        assert_eq!(rules.chain_bonus, Some(ChainBonus::default()));
+    }
+}
@@ -0,0 +1,269 @@
+use crate::assets::LoadState;
+use crate::input_map::{save_input_map, InputAction, InputMap};
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+
+const TOGGLE_SETTINGS_KEY: KeyCode = KeyCode::F1;
+const TOGGLE_SETTINGS_BUTTON: GamepadButtonType = GamepadButtonType::Start;
+const CANCEL_REBIND_KEY: KeyCode = KeyCode::Escape;
+const ROW_COLOR: Color = Color::rgba(1.0, 1.0, 1.0, 0.08);
+const LISTENING_ROW_COLOR: Color = Color::rgba(1.0, 0.9, 0.35, 0.35);
+
+#[derive(Resource, Default, Clone, PartialEq, Eq, Debug)]
+pub struct SettingsScreen {
+    pub open: bool,
+    pub listening: Option<InputAction>,
+}
+
+#[derive(Component, Clone, PartialEq, Eq, PartialOrd, Ord, Reflect)]
+pub struct SettingsScreenMarker;
+
+#[derive(Component, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Reflect)]
+pub struct RebindButton(pub InputAction);
+
+#[derive(Component, Clone, PartialEq, Eq, PartialOrd, Ord, Reflect)]
+pub struct ResetBindingsButton;
+
+// position of a row for keyboard and gamepad navigation, the reset button comes last
+#[derive(Component, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Reflect)]
+pub struct SettingsRow(pub usize);
+
+pub const SETTINGS_ROW_COUNT: usize = InputAction::ALL.len() + 1;
+
+#[derive(Event, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SettingsRowPressed(pub usize);
+
+fn spawn_settings_screen(
+    mut commands: Commands,
+    assets: Res<AssetServer>,
+    input_map: Res<InputMap>,
+) {
+    let font: Handle<Font> = assets.load("ui/simple-pixel.ttf");
+    let text_style = TextStyle {
+        font: font.clone(),
+        font_size: 18.0,
+        color: Color::WHITE,
+    };
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+            background_color: BackgroundColor(Color::rgba(0.0, 0.0, 0.0, 0.75)),
+            focus_policy: FocusPolicy::Block,
+            visibility: Visibility::Hidden,
+            z_index: ZIndex::Global(3),
+            ..default()
+        })
+        .insert(SettingsScreenMarker)
+        .with_children(|parent| {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Px(560.0),
+                        padding: UiRect::all(Val::Px(16.0)),
+                        row_gap: Val::Px(6.0),
+                        flex_direction: FlexDirection::Column,
+                        ..default()
+                    },
+                    background_color: BackgroundColor(Color::rgb(0.08, 0.0, 0.07)),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        format!(
+                            "Controls ({:?} / {:?} to close, select a row to replace its binding)",
+                            TOGGLE_SETTINGS_KEY, TOGGLE_SETTINGS_BUTTON
+                        ),
+                        text_style.clone(),
+                    ));
+                    for (index, action) in InputAction::ALL.into_iter().enumerate() {
+                        parent
+                            .spawn(ButtonBundle {
+                                style: Style {
+                                    width: Val::Percent(100.0),
+                                    padding: UiRect::all(Val::Px(4.0)),
+                                    justify_content: JustifyContent::SpaceBetween,
+                                    ..default()
+                                },
+                                background_color: BackgroundColor(ROW_COLOR),
+                                ..default()
+                            })
+                            .insert(RebindButton(action))
+                            .insert(SettingsRow(index))
+                            .insert(Outline::new(Val::Px(2.0), Val::Px(0.0), Color::NONE))
+                            .with_children(|parent| {
+                                parent.spawn(TextBundle::from_section(
+                                    format!("{:?}", action),
+                                    text_style.clone(),
+                                ));
+                                parent.spawn(TextBundle::from_section(
+                                    input_map.describe(action),
+                                    text_style.clone(),
+                                ));
+                            });
+                    }
+                    parent
+                        .spawn(ButtonBundle {
+                            style: Style {
+                                padding: UiRect::all(Val::Px(4.0)),
+                                margin: UiRect::top(Val::Px(8.0)),
+                                ..default()
+                            },
+                            background_color: BackgroundColor(ROW_COLOR),
+                            ..default()
+                        })
+                        .insert(ResetBindingsButton)
+                        .insert(SettingsRow(InputAction::ALL.len()))
+                        .insert(Outline::new(Val::Px(2.0), Val::Px(0.0), Color::NONE))
+                        .with_children(|parent| {
+                            parent.spawn(TextBundle::from_section(
+                                "Reset to defaults",
+                                text_style.clone(),
+                            ));
+                        });
+                });
+        });
+}
+
+fn toggle_settings_screen(
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    mut settings_screen: ResMut<SettingsScreen>,
+    mut screen_query: Query<&mut Visibility, With<SettingsScreenMarker>>,
+) {
+    let toggled = keys.just_pressed(TOGGLE_SETTINGS_KEY)
+        || gamepads.iter().any(|gamepad| {
+            gamepad_buttons.just_pressed(GamepadButton::new(gamepad, TOGGLE_SETTINGS_BUTTON))
+        });
+    if !toggled {
+        return;
+    }
+    settings_screen.open = !settings_screen.open;
+    settings_screen.listening = None;
+    for mut visibility in screen_query.iter_mut() {
+        *visibility = match settings_screen.open {
+            true => Visibility::Visible,
+            false => Visibility::Hidden,
+        };
+    }
+}
+
+fn press_settings_rows(
+    interaction_query: Query<(&Interaction, &SettingsRow), Changed<Interaction>>,
+    mut row_evw: EventWriter<SettingsRowPressed>,
+) {
+    for (interaction, row) in interaction_query.iter() {
+        if *interaction == Interaction::Pressed {
+            row_evw.send(SettingsRowPressed(row.0));
+        }
+    }
+}
+
+// runs after listen_for_binding so the press that picked a row is not taken as its new binding
+fn start_rebinding(
+    mut row_evr: EventReader<SettingsRowPressed>,
+    mut settings_screen: ResMut<SettingsScreen>,
+    mut input_map: ResMut<InputMap>,
+) {
+    for SettingsRowPressed(index) in row_evr.read() {
+        if !settings_screen.open {
+            continue;
+        }
+        match InputAction::ALL.get(*index) {
+            Some(action) => settings_screen.listening = Some(*action),
+            None => {
+                *input_map = InputMap::default();
+                settings_screen.listening = None;
+                save_input_map(&input_map);
+            }
+        }
+    }
+}
+
+// a taken key or button is swapped with the one being replaced, see InputMap::rebind_key
+fn listen_for_binding(
+    mut keys: ResMut<ButtonInput<KeyCode>>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    mut settings_screen: ResMut<SettingsScreen>,
+    mut input_map: ResMut<InputMap>,
+) {
+    let action = match settings_screen.listening {
+        Some(x) => x,
+        None => {
+            return;
+        }
+    };
+    // the cancel press is consumed so it does not also reach the board
+    if keys.just_pressed(CANCEL_REBIND_KEY) {
+        keys.clear_just_pressed(CANCEL_REBIND_KEY);
+        settings_screen.listening = None;
+        return;
+    }
+    match (
+        keys.get_just_pressed().next(),
+        gamepad_buttons.get_just_pressed().next(),
+    ) {
+        (Some(key), _) => input_map.rebind_key(action, *key),
+        (None, Some(button)) => input_map.rebind_gamepad_button(action, button.button_type),
+        (None, None) => {
+            return;
+        }
+    }
+    settings_screen.listening = None;
+    save_input_map(&input_map);
+}
+
+fn update_settings_screen(
+    settings_screen: Res<SettingsScreen>,
+    input_map: Res<InputMap>,
+    mut row_query: Query<(&RebindButton, &Children, &mut BackgroundColor)>,
+    mut text_query: Query<&mut Text>,
+) {
+    if !settings_screen.is_changed() && !input_map.is_changed() {
+        return;
+    }
+    for (rebind, children, mut background) in row_query.iter_mut() {
+        let listening = settings_screen.listening == Some(rebind.0);
+        background.0 = match listening {
+            true => LISTENING_ROW_COLOR,
+            false => ROW_COLOR,
+        };
+        if let Some(mut text) = children.get(1).and_then(|x| text_query.get_mut(*x).ok()) {
+            text.sections[0].value = match listening {
+                true => format!(
+                    "press a key or button, {:?} to cancel...",
+                    CANCEL_REBIND_KEY
+                ),
+                false => input_map.describe(rebind.0),
+            }
+        }
+    }
+}
+
+pub struct SettingsScreenPlugin;
+
+impl Plugin for SettingsScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SettingsScreen>()
+            .add_event::<SettingsRowPressed>()
+            .add_systems(OnEnter(LoadState::Loaded), spawn_settings_screen)
+            .add_systems(
+                Update,
+                (
+                    toggle_settings_screen,
+                    press_settings_rows,
+                    listen_for_binding,
+                    start_rebinding,
+                    update_settings_screen,
+                )
+                    .chain(),
+            );
+    }
+}
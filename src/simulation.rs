@@ -0,0 +1,239 @@
+use crate::ai::choose_action;
+use crate::cards::{Card, CardRarity};
+use crate::constants::CARD_SLOT_COUNT;
+use crate::game_state::{CardSlot, CardSlotType, Team};
+use crate::game_ui_controller::GameController;
+use crate::protocol::{PlayerAction, StateDelta};
+use crate::rules::MatchRules;
+use bevy::prelude::*;
+use bevy_rand::prelude::WyRand;
+use rand::SeedableRng;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+// stops matches where neither side gets through, they count as draws
+pub const DEFAULT_MAX_TURNS: u32 = 200;
+// no turn needs this many actions, a bot that gets there is stuck
+const MAX_ACTIONS_PER_TURN: u32 = 64;
+
+#[derive(Default, Clone, Debug)]
+struct CardTally {
+    rarity: CardRarity,
+    appearances: u32,
+    wins: u32,
+    plays: u32,
+    damage: u64,
+    turns_on_board: u64,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct MatchResult {
+    pub seed: u64,
+    pub winner: Option<Team>,
+    pub turns: u32,
+}
+
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct CardReport {
+    pub name: String,
+    pub rarity: CardRarity,
+    // matches in which a team played the card at least once
+    pub appearances: u32,
+    pub plays: u32,
+    pub win_rate: f32,
+    pub play_rate: f32,
+    pub average_damage: f32,
+    pub average_survival_turns: f32,
+}
+
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct SimulationReport {
+    pub matches: u32,
+    pub red_wins: u32,
+    pub blue_wins: u32,
+    pub draws: u32,
+    pub cards: Vec<CardReport>,
+}
+
+// bot against bot matches over the real rules, collecting how every card fared
+#[derive(Default, Clone, Debug)]
+pub struct Simulation {
+    results: Vec<MatchResult>,
+    tallies: BTreeMap<String, CardTally>,
+}
+
+fn get_play_row(
+    game_ui_controller: &GameController,
+    team: Team,
+    cards: &Assets<Card>,
+) -> Vec<String> {
+    (0..CARD_SLOT_COUNT)
+        .filter_map(|id| {
+            game_ui_controller.get_card(&CardSlot {
+                id,
+                team,
+                slot_type: CardSlotType::Play,
+            })
+        })
+        .map(|(card, _)| cards.get(card).unwrap().name.clone())
+        .collect()
+}
+
+// the cards in after that were not in before, rows only shift so order is ignored
+fn get_entered_cards(before: &[String], after: &[String]) -> Vec<String> {
+    let mut before = before.to_vec();
+    let mut entered = vec![];
+    for name in after {
+        match before.iter().position(|x| x == name) {
+            Some(x) => {
+                before.remove(x);
+            }
+            None => entered.push(name.clone()),
+        }
+    }
+    entered
+}
+
+fn ratio(count: u64, total: u64) -> f32 {
+    match total {
+        0 => 0.0,
+        _ => count as f32 / total as f32,
+    }
+}
+
+impl Simulation {
+    // every card is listed in the report, including the ones no bot ever played
+    pub fn new(cards: &Assets<Card>) -> Self {
+        Simulation {
+            results: vec![],
+            tallies: cards
+                .iter()
+                .map(|(_, card)| {
+                    let tally = CardTally {
+                        rarity: card.rarity,
+                        ..default()
+                    };
+                    (card.name.clone(), tally)
+                })
+                .collect(),
+        }
+    }
+
+    pub fn run_match(
+        &mut self,
+        seed: u64,
+        cards: &Assets<Card>,
+        rules: &MatchRules,
+        max_turns: u32,
+    ) -> MatchResult {
+        let mut rng = WyRand::from_seed(seed.to_le_bytes());
+        // the bots draw from their own streams so their choices do not shift the match draws
+        let mut bots: BTreeMap<Team, WyRand> = [Team::Red, Team::Blue]
+            .into_iter()
+            .map(|team| (team, WyRand::seed_from_u64(seed ^ (team as u64 + 1))))
+            .collect();
+        let mut game_ui_controller = GameController::new(cards, rules, &mut rng);
+        let mut played: BTreeMap<Team, BTreeSet<String>> = BTreeMap::new();
+        let mut actions_this_turn = 0;
+        let winner = loop {
+            if let Some(loser) = [Team::Red, Team::Blue]
+                .into_iter()
+                .find(|team| game_ui_controller.get_team_health(*team) == 0)
+            {
+                break Some(!loser);
+            }
+            if game_ui_controller.get_turn() > max_turns || actions_this_turn > MAX_ACTIONS_PER_TURN
+            {
+                break None;
+            }
+            let team = game_ui_controller.get_current_team();
+            let turn = game_ui_controller.get_turn();
+            let action = match choose_action(
+                &game_ui_controller,
+                team,
+                cards,
+                bots.get_mut(&team).unwrap(),
+            ) {
+                Some(x) => x,
+                None => PlayerAction::EndTurn,
+            };
+            let before = get_play_row(&game_ui_controller, team, cards);
+            match game_ui_controller.apply_action(team, &action, cards, &mut rng) {
+                Ok(_) => {}
+                Err(err) => {
+                    warn!("seed {}: the bot tried {:?} but {}", seed, action, err);
+                    break None;
+                }
+            }
+            actions_this_turn += 1;
+            for name in get_entered_cards(&before, &get_play_row(&game_ui_controller, team, cards))
+            {
+                self.tallies.entry(name.clone()).or_default().plays += 1;
+                played.entry(team).or_default().insert(name);
+            }
+            game_ui_controller.resolve_moves(cards, &mut rng);
+            for delta in game_ui_controller.take_deltas() {
+                if let StateDelta::Attacked { card, damage, .. } = delta {
+                    self.tallies.entry(card).or_default().damage += damage as u64
+                }
+            }
+            game_ui_controller.clear_card_modifications();
+            // every card still standing when a turn ends survived it
+            if game_ui_controller.get_turn() != turn {
+                actions_this_turn = 0;
+                for team in [Team::Red, Team::Blue] {
+                    for name in get_play_row(&game_ui_controller, team, cards) {
+                        self.tallies.entry(name).or_default().turns_on_board += 1;
+                    }
+                }
+            }
+        };
+        for (team, names) in played {
+            for name in names {
+                let tally = self.tallies.entry(name).or_default();
+                tally.appearances += 1;
+                if winner == Some(team) {
+                    tally.wins += 1;
+                }
+            }
+        }
+        let result = MatchResult {
+            seed,
+            winner,
+            turns: game_ui_controller.get_turn(),
+        };
+        self.results.push(result);
+        result
+    }
+
+    pub fn report(&self) -> SimulationReport {
+        let matches = self.results.len() as u32;
+        let wins = |team: Team| {
+            self.results
+                .iter()
+                .filter(|x| x.winner == Some(team))
+                .count() as u32
+        };
+        SimulationReport {
+            matches,
+            red_wins: wins(Team::Red),
+            blue_wins: wins(Team::Blue),
+            draws: self.results.iter().filter(|x| x.winner.is_none()).count() as u32,
+            cards: self
+                .tallies
+                .iter()
+                .map(|(name, tally)| CardReport {
+                    name: name.clone(),
+                    rarity: tally.rarity,
+                    appearances: tally.appearances,
+                    plays: tally.plays,
+                    win_rate: ratio(tally.wins as u64, tally.appearances as u64),
+                    // each match gives both teams a chance to play the card
+                    play_rate: ratio(tally.appearances as u64, matches as u64 * 2),
+                    average_damage: ratio(tally.damage, tally.plays as u64),
+                    average_survival_turns: ratio(tally.turns_on_board, tally.plays as u64),
+                })
+                .collect(),
+        }
+    }
+}
@@ -2,18 +2,28 @@ use crate::cards::{get_card_back_image, CardBack, CardBackType};
 use crate::constants::CARD_SLOT_COUNT;
 
 use crate::game_state::{
-    BlueHealthMarker, CardDeckMarker, CardSlot, CardSlotMarker, CardSlotType, DiscardMarker,
-    NextTurnCardType, RedHealthMarker, Team,
+    BlueHealthMarker, CardColorPipMarker, CardDeckMarker, CardEquipmentTextMarker,
+    CardRarityGemMarker, CardSlot, CardSlotMarker, CardSlotType, CardTextMarker,
+    CardTypeIconMarker, ChainLinkMarker, DiscardMarker, DrawBlockedMarker, EndTurnButtonMarker,
+    EnergyMarker, RedHealthMarker, StatusIconMarker, Team, UndoButtonMarker,
 };
+use crate::status_effects::STATUS_ICON_COUNT;
 use bevy::prelude::*;
 use bevy::render::texture::{
     ImageAddressMode, ImageLoaderSettings, ImageSampler, ImageSamplerDescriptor,
 };
 
+// top to bottom order of the slot rows on screen
+pub const SLOT_ROWS: [(Team, CardSlotType); 4] = [
+    (Team::Blue, CardSlotType::Hand),
+    (Team::Blue, CardSlotType::Play),
+    (Team::Red, CardSlotType::Play),
+    (Team::Red, CardSlotType::Hand),
+];
+
 pub fn spawn_game_ui(
     mut commands: Commands,
     card_backs: Res<Assets<CardBack>>,
-    card_type_state: Res<State<NextTurnCardType>>,
     assets: Res<AssetServer>,
 ) {
     let sampler_desc = ImageSamplerDescriptor {
@@ -64,7 +74,7 @@ pub fn spawn_game_ui(
                     ..default()
                 })
                 .with_children(|parent| {
-                    spawn_card_piles(parent, &card_backs, &card_type_state, font.clone(), &assets)
+                    spawn_card_piles(parent, &card_backs, font.clone(), &assets)
                 });
             parent
                 .spawn(NodeBundle {
@@ -78,38 +88,16 @@ pub fn spawn_game_ui(
                     ..default()
                 })
                 .with_children(|parent| {
-                    spawn_slots_for_team(
-                        parent,
-                        Team::Blue,
-                        CardSlotType::Hand,
-                        &Color::rgba(0.0, 0.0, 0.0, 0.0),
-                        slot.clone(),
-                        font.clone(),
-                    );
-                    spawn_slots_for_team(
-                        parent,
-                        Team::Blue,
-                        CardSlotType::Play,
-                        &Color::rgba(0.0, 0.0, 0.0, 0.0),
-                        slot.clone(),
-                        font.clone(),
-                    );
-                    spawn_slots_for_team(
-                        parent,
-                        Team::Red,
-                        CardSlotType::Play,
-                        &Color::rgba(0.0, 0.0, 0.0, 0.0),
-                        slot.clone(),
-                        font.clone(),
-                    );
-                    spawn_slots_for_team(
-                        parent,
-                        Team::Red,
-                        CardSlotType::Hand,
-                        &Color::rgba(0.0, 0.0, 0.0, 0.0),
-                        slot.clone(),
-                        font.clone(),
-                    );
+                    for (team, slot_type) in SLOT_ROWS {
+                        spawn_slots_for_team(
+                            parent,
+                            team,
+                            slot_type,
+                            &Color::rgba(0.0, 0.0, 0.0, 0.0),
+                            slot.clone(),
+                            font.clone(),
+                        );
+                    }
                 });
         });
 }
@@ -155,6 +143,7 @@ fn spawn_slots_for_team<'a>(
                         ..default()
                     })
                     .insert(CardSlotMarker)
+                    .insert(Outline::new(Val::Px(3.0), Val::Px(0.0), Color::NONE))
                     .with_children(|parent| {
                         parent
                             .spawn(ImageBundle {
@@ -166,7 +155,7 @@ fn spawn_slots_for_team<'a>(
                                 visibility: Visibility::Hidden,
                                 ..default()
                             })
-                            .insert(slot)
+                            .insert(slot.clone())
                             .with_children(|parent| {
                                 parent
                                     .spawn(NodeBundle {
@@ -181,20 +170,21 @@ fn spawn_slots_for_team<'a>(
                                         ..default()
                                     })
                                     .with_children(|parent| {
-                                        parent.spawn(TextBundle::from_section(
-                                            "",
-                                            TextStyle {
-                                                font: font.clone(),
-                                                font_size: 10.0,
-                                                color: Color::Rgba {
-                                                    red: 0.4375,
-                                                    green: 0.0,
-                                                    blue: 0.36328125,
-                                                    alpha: 1.0,
+                                        parent
+                                            .spawn(TextBundle::from_section(
+                                                "",
+                                                TextStyle {
+                                                    font: font.clone(),
+                                                    font_size: 10.0,
+                                                    color: Color::Rgba {
+                                                        red: 0.4375,
+                                                        green: 0.0,
+                                                        blue: 0.36328125,
+                                                        alpha: 1.0,
+                                                    },
                                                 },
-                                                ..default()
-                                            },
-                                        ));
+                                            ))
+                                            .insert(CardTextMarker::Text);
                                     });
 
                                 parent
@@ -210,53 +200,264 @@ fn spawn_slots_for_team<'a>(
                                         ..default()
                                     })
                                     .with_children(|parent| {
-                                        parent.spawn(TextBundle::from_section(
+                                        parent
+                                            .spawn(TextBundle::from_section(
+                                                "",
+                                                TextStyle {
+                                                    font: font.clone(),
+                                                    font_size: 13.0,
+                                                    color: Color::Rgba {
+                                                        red: 1.0,
+                                                        green: 1.0,
+                                                        blue: 1.0,
+                                                        alpha: 1.0,
+                                                    },
+                                                },
+                                            ))
+                                            .insert(CardTextMarker::Hp);
+                                    });
+
+                                parent
+                                    .spawn(NodeBundle {
+                                        style: Style {
+                                            left: Val::Percent(45.0 / 72.0 * 100.0),
+                                            top: Val::Percent(8.0 / 102.0 * 100.0),
+                                            width: Val::Percent(19.0 / 72.0 * 100.0),
+                                            height: Val::Percent(15.0 / 102.0 * 100.0),
+                                            justify_content: JustifyContent::End,
+                                            position_type: PositionType::Absolute,
+                                            ..default()
+                                        },
+                                        ..default()
+                                    })
+                                    .with_children(|parent| {
+                                        parent
+                                            .spawn(TextBundle::from_section(
+                                                "",
+                                                TextStyle {
+                                                    font: font.clone(),
+                                                    font_size: 13.0,
+                                                    color: Color::Rgba {
+                                                        red: 1.0,
+                                                        green: 0.55,
+                                                        blue: 0.4,
+                                                        alpha: 1.0,
+                                                    },
+                                                },
+                                            ))
+                                            .insert(CardTextMarker::Damage);
+                                    });
+
+                                for (pip, left) in [
+                                    (CardColorPipMarker::First, 0.0),
+                                    (CardColorPipMarker::Last, 66.0),
+                                ] {
+                                    parent
+                                        .spawn(NodeBundle {
+                                            style: Style {
+                                                left: Val::Percent(left / 72.0 * 100.0),
+                                                top: Val::Percent(46.0 / 102.0 * 100.0),
+                                                width: Val::Percent(6.0 / 72.0 * 100.0),
+                                                height: Val::Percent(10.0 / 102.0 * 100.0),
+                                                position_type: PositionType::Absolute,
+                                                ..default()
+                                            },
+                                            ..default()
+                                        })
+                                        .insert(pip);
+                                }
+
+                                parent
+                                    .spawn(ImageBundle {
+                                        style: Style {
+                                            left: Val::Percent(30.0 / 72.0 * 100.0),
+                                            top: Val::Percent(6.0 / 102.0 * 100.0),
+                                            width: Val::Percent(12.0 / 72.0 * 100.0),
+                                            aspect_ratio: Some(72.0 / 102.0),
+                                            position_type: PositionType::Absolute,
+                                            ..default()
+                                        },
+                                        ..default()
+                                    })
+                                    .insert(CardTypeIconMarker);
+
+                                parent
+                                    .spawn(NodeBundle {
+                                        style: Style {
+                                            left: Val::Percent(33.0 / 72.0 * 100.0),
+                                            top: Val::Percent(92.0 / 102.0 * 100.0),
+                                            width: Val::Percent(6.0 / 72.0 * 100.0),
+                                            aspect_ratio: Some(1.0),
+                                            position_type: PositionType::Absolute,
+                                            ..default()
+                                        },
+                                        ..default()
+                                    })
+                                    .insert(Outline::new(
+                                        Val::Px(1.0),
+                                        Val::Px(0.0),
+                                        Color::rgb(0.1, 0.1, 0.1),
+                                    ))
+                                    .insert(CardRarityGemMarker);
+
+                                // attached equipment is listed just under the card
+                                parent
+                                    .spawn(TextBundle {
+                                        text: Text::from_section(
                                             "",
                                             TextStyle {
                                                 font: font.clone(),
-                                                font_size: 13.0,
-                                                color: Color::Rgba {
-                                                    red: 1.0,
-                                                    green: 1.0,
-                                                    blue: 1.0,
-                                                    alpha: 1.0,
-                                                },
+                                                font_size: 11.0,
+                                                color: Color::rgb(0.85, 0.85, 0.85),
+                                            },
+                                        ),
+                                        style: Style {
+                                            top: Val::Percent(100.0),
+                                            position_type: PositionType::Absolute,
+                                            ..default()
+                                        },
+                                        z_index: ZIndex::Local(1),
+                                        ..default()
+                                    })
+                                    .insert(CardEquipmentTextMarker);
+
+                                // statuses are stacked down the left edge of the card in play
+                                if slot_type == CardSlotType::Play {
+                                    parent
+                                        .spawn(NodeBundle {
+                                            style: Style {
+                                                left: Val::Percent(2.0),
+                                                top: Val::Percent(26.0),
+                                                width: Val::Percent(22.0),
+                                                flex_direction: FlexDirection::Column,
+                                                row_gap: Val::Px(1.0),
+                                                position_type: PositionType::Absolute,
                                                 ..default()
                                             },
-                                        ));
-                                    });
+                                            z_index: ZIndex::Local(1),
+                                            ..default()
+                                        })
+                                        .with_children(|parent| {
+                                            for index in 0..STATUS_ICON_COUNT {
+                                                parent
+                                                    .spawn(NodeBundle {
+                                                        style: Style {
+                                                            width: Val::Percent(100.0),
+                                                            aspect_ratio: Some(1.0),
+                                                            justify_content: JustifyContent::Center,
+                                                            align_items: AlignItems::Center,
+                                                            display: Display::None,
+                                                            ..default()
+                                                        },
+                                                        ..default()
+                                                    })
+                                                    .insert(Outline::new(
+                                                        Val::Px(1.0),
+                                                        Val::Px(0.0),
+                                                        Color::rgb(0.1, 0.1, 0.1),
+                                                    ))
+                                                    .insert(StatusIconMarker {
+                                                        slot: slot.clone(),
+                                                        index,
+                                                    })
+                                                    .with_children(|parent| {
+                                                        parent.spawn(TextBundle::from_section(
+                                                            "",
+                                                            TextStyle {
+                                                                font: font.clone(),
+                                                                font_size: 8.0,
+                                                                color: Color::rgb(0.1, 0.1, 0.1),
+                                                            },
+                                                        ));
+                                                    });
+                                            }
+                                        });
+                                }
                             });
                     });
+                // the gaps between play slots show the color chains, growing keeps the spacing
+                if slot_type == CardSlotType::Play && id + 1 < CARD_SLOT_COUNT {
+                    parent
+                        .spawn(NodeBundle {
+                            style: Style {
+                                flex_grow: 1.0,
+                                height: Val::Px(6.0),
+                                ..default()
+                            },
+                            ..default()
+                        })
+                        .insert(ChainLinkMarker { team, id });
+                }
             }
         });
 }
 
-fn spawn_card_piles<'a>(
-    parent: &mut ChildBuilder<'a>,
-    card_backs: &Res<Assets<CardBack>>,
-    card_type_state: &Res<State<NextTurnCardType>>,
+fn spawn_text_button(
+    parent: &mut ChildBuilder,
+    label: &str,
     font: Handle<Font>,
-    assets: &Res<AssetServer>,
+    marker: impl Component,
 ) {
-    let discard_back = get_card_back_image(card_backs, CardBackType::Discard);
-    let current_back = get_card_back_image(
-        card_backs,
-        CardBackType::CardType(card_type_state.get().0.clone()),
-    );
     parent
         .spawn(ButtonBundle {
             style: Style {
                 width: Val::Percent(100.0),
-                aspect_ratio: Some(72.0 / 102.0),
+                padding: UiRect::all(Val::Px(4.0)),
+                justify_content: JustifyContent::Center,
                 ..default()
             },
-            image: UiImage {
-                texture: current_back,
+            background_color: BackgroundColor(Color::rgba(1.0, 1.0, 1.0, 0.08)),
+            ..default()
+        })
+        .insert(marker)
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                label,
+                TextStyle {
+                    font,
+                    font_size: 20.0,
+                    ..default()
+                },
+            ));
+        });
+}
+
+// the backs are filled in from the controller once the piles exist
+fn spawn_draw_piles(parent: &mut ChildBuilder, team: Team) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                column_gap: Val::Px(4.0),
                 ..default()
             },
             ..default()
         })
-        .insert(CardDeckMarker);
+        .with_children(|parent| {
+            for pile in 0..2 {
+                parent
+                    .spawn(ButtonBundle {
+                        style: Style {
+                            width: Val::Percent(100.0),
+                            aspect_ratio: Some(72.0 / 102.0),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .insert(CardDeckMarker { team, pile })
+                    .insert(Outline::new(Val::Px(3.0), Val::Px(0.0), Color::NONE));
+            }
+        });
+}
+
+fn spawn_card_piles<'a>(
+    parent: &mut ChildBuilder<'a>,
+    card_backs: &Res<Assets<CardBack>>,
+    font: Handle<Font>,
+    assets: &Res<AssetServer>,
+) {
+    let discard_back = get_card_back_image(card_backs, CardBackType::Discard);
+    spawn_draw_piles(parent, Team::Blue);
 
     parent
         .spawn(ImageBundle {
@@ -313,7 +514,35 @@ fn spawn_card_piles<'a>(
         });
 
     parent
-        .spawn(ImageBundle {
+        .spawn(TextBundle::from_section(
+            "",
+            TextStyle {
+                font: font.clone(),
+                font_size: 20.0,
+                color: Color::rgb(1.0, 0.9, 0.35),
+            },
+        ))
+        .insert(EnergyMarker);
+
+    spawn_text_button(parent, "Undo", font.clone(), UndoButtonMarker);
+    spawn_text_button(parent, "End turn", font.clone(), EndTurnButtonMarker);
+
+    // explains why the deck can not be drawn from, empty otherwise
+    parent
+        .spawn(TextBundle::from_section(
+            "",
+            TextStyle {
+                font: font.clone(),
+                font_size: 14.0,
+                color: Color::rgb(0.9, 0.25, 0.2),
+            },
+        ))
+        .insert(DrawBlockedMarker);
+
+    spawn_draw_piles(parent, Team::Red);
+
+    parent
+        .spawn(ButtonBundle {
             style: Style {
                 width: Val::Percent(100.0),
                 aspect_ratio: Some(72.0 / 102.0),
@@ -325,5 +554,6 @@ fn spawn_card_piles<'a>(
             },
             ..default()
         })
-        .insert(DiscardMarker);
+        .insert(DiscardMarker)
+        .insert(Outline::new(Val::Px(3.0), Val::Px(0.0), Color::NONE));
 }
@@ -0,0 +1,182 @@
+use crate::cards::Card;
+use crate::constants::CARD_SLOT_COUNT;
+use crate::game_state::{
+    CardSlot, CardSlotType, CardStats, Status, StatusEffect, StatusIconMarker, Team,
+};
+use crate::game_ui_controller::GameController;
+use bevy::prelude::*;
+use std::mem::discriminant;
+
+// statuses past this many are still in effect, they just get no icon
+pub const STATUS_ICON_COUNT: usize = 4;
+
+impl StatusEffect {
+    pub fn get_name(&self) -> String {
+        match self {
+            StatusEffect::Poisoned(x) => format!("Poisoned {}", x),
+            StatusEffect::Burning(x) => format!("Burning {}", x),
+            StatusEffect::Stunned => "Stunned".to_string(),
+            StatusEffect::Frozen => "Frozen".to_string(),
+            StatusEffect::Shielded(x) => format!("Shielded {}", x),
+            StatusEffect::Weakened(x) => format!("Weakened {}", x),
+        }
+    }
+
+    pub fn get_icon_text(&self) -> &'static str {
+        match self {
+            StatusEffect::Poisoned(_) => "Ps",
+            StatusEffect::Burning(_) => "Bn",
+            StatusEffect::Stunned => "St",
+            StatusEffect::Frozen => "Fz",
+            StatusEffect::Shielded(_) => "Sh",
+            StatusEffect::Weakened(_) => "Wk",
+        }
+    }
+
+    pub fn ui_color(&self) -> Color {
+        match self {
+            StatusEffect::Poisoned(_) => Color::rgb(0.3, 0.75, 0.2),
+            StatusEffect::Burning(_) => Color::rgb(1.0, 0.45, 0.1),
+            StatusEffect::Stunned => Color::rgb(0.95, 0.85, 0.2),
+            StatusEffect::Frozen => Color::rgb(0.5, 0.85, 1.0),
+            StatusEffect::Shielded(_) => Color::rgb(0.75, 0.75, 0.8),
+            StatusEffect::Weakened(_) => Color::rgb(0.6, 0.35, 0.7),
+        }
+    }
+}
+
+impl Status {
+    pub fn get_description(&self) -> String {
+        match self.turns {
+            Some(0) => format!("{} until its next turn", self.effect.get_name()),
+            Some(turns) => format!("{} for {} more turns", self.effect.get_name(), turns),
+            None => self.effect.get_name(),
+        }
+    }
+
+    // a status that never runs out outlasts any timed one
+    fn get_strength(&self) -> (StatusEffect, u32) {
+        (self.effect, self.turns.unwrap_or(u32::MAX))
+    }
+}
+
+impl GameController {
+    // a status replaces one of the same kind unless that one is stronger
+    pub fn give_status(&mut self, slot: &CardSlot, status: Status, cards: &Assets<Card>) {
+        let (card, stats) = match self.get_card(slot) {
+            Some(x) => x,
+            None => {
+                return;
+            }
+        };
+        let same_kind = |x: &&Status| discriminant(&x.effect) == discriminant(&status.effect);
+        if stats
+            .statuses
+            .iter()
+            .filter(same_kind)
+            .any(|x| x.get_strength() >= status.get_strength())
+        {
+            return;
+        }
+        self.log(format!(
+            "{} became {}",
+            cards.get(card).unwrap().name,
+            status.get_description()
+        ));
+        let mut statuses: Vec<Status> = stats
+            .statuses
+            .iter()
+            .filter(|x| !same_kind(x))
+            .copied()
+            .collect();
+        statuses.push(status);
+        self.push_card_at(slot.clone(), card, CardStats { statuses, ..stats });
+    }
+
+    // counts the timed statuses of the team's play row down as its turn starts
+    pub fn tick_statuses(&mut self, team: Team, cards: &Assets<Card>) {
+        for id in 0..CARD_SLOT_COUNT {
+            self.tick_card_statuses(
+                &CardSlot {
+                    id,
+                    team,
+                    slot_type: CardSlotType::Play,
+                },
+                cards,
+            );
+        }
+    }
+
+    fn tick_card_statuses(&mut self, slot: &CardSlot, cards: &Assets<Card>) {
+        let (card, stats) = match self.get_card(slot) {
+            Some(x) if x.1.statuses.iter().any(|x| x.turns.is_some()) => x,
+            _ => {
+                return;
+            }
+        };
+        let mut statuses = vec![];
+        for status in stats.statuses.iter() {
+            match status.turns {
+                Some(0) => self.log(format!(
+                    "{} is no longer {}",
+                    cards.get(card).unwrap().name,
+                    status.effect.get_name()
+                )),
+                Some(turns) => statuses.push(Status {
+                    turns: Some(turns - 1),
+                    ..*status
+                }),
+                None => statuses.push(*status),
+            }
+        }
+        self.push_card_at(slot.clone(), card, CardStats { statuses, ..stats });
+    }
+}
+
+fn update_status_icons(
+    game_ui_controller_query: Query<&GameController>,
+    mut icon_query: Query<(
+        &StatusIconMarker,
+        &mut Style,
+        &mut BackgroundColor,
+        &Children,
+    )>,
+    mut text_query: Query<&mut Text>,
+) {
+    let game_ui_controller = match game_ui_controller_query.get_single() {
+        Ok(x) => x,
+        _ => {
+            return;
+        }
+    };
+    for (icon, mut style, mut background, children) in icon_query.iter_mut() {
+        let status = game_ui_controller
+            .get_card(&icon.slot)
+            .and_then(|(_, stats)| stats.statuses.get(icon.index).copied());
+        let (display, color, label) = match status {
+            Some(x) => (Display::Flex, x.effect.ui_color(), x.effect.get_icon_text()),
+            None => (Display::None, Color::NONE, ""),
+        };
+        if style.display != display {
+            style.display = display;
+        }
+        if background.0 != color {
+            background.0 = color;
+        }
+        for child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(*child) {
+                if text.sections[0].value != label {
+                    text.sections[0].value = label.to_string();
+                }
+            }
+        }
+    }
+}
+
+pub struct StatusEffectPlugin;
+
+impl Plugin for StatusEffectPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, update_status_icons);
+    }
+}
@@ -0,0 +1,435 @@
+use crate::cards::{Card, Effect, TriggerKind};
+use crate::constants::CARD_SLOT_COUNT;
+use crate::game_state::{Buff, CardSlot, CardSlotType, CardStats, StatusEffect, Team};
+use crate::game_ui_controller::GameController;
+use bevy::prelude::*;
+use rand::Rng;
+use std::collections::VecDeque;
+
+// a chain of triggers feeding each other is cut off after this many effects
+const MAX_RESOLVED_EFFECTS: usize = 128;
+
+// an effect waiting its turn, the slot is where its card stood when it triggered
+#[derive(Clone, Debug)]
+pub struct QueuedEffect {
+    pub card: AssetId<Card>,
+    pub slot: CardSlot,
+    pub effect: Effect,
+}
+
+fn get_play_slot(team: Team, id: usize) -> CardSlot {
+    CardSlot {
+        id,
+        team,
+        slot_type: CardSlotType::Play,
+    }
+}
+
+fn get_neighbors(slot: &CardSlot) -> Vec<CardSlot> {
+    [slot.id.checked_sub(1), Some(slot.id + 1)]
+        .into_iter()
+        .flatten()
+        .filter(|id| *id < CARD_SLOT_COUNT)
+        .map(|id| get_play_slot(slot.team, id))
+        .collect()
+}
+
+impl GameController {
+    pub fn queue_triggers(
+        &self,
+        queue: &mut VecDeque<QueuedEffect>,
+        when: TriggerKind,
+        slot: &CardSlot,
+        card: AssetId<Card>,
+        cards: &Assets<Card>,
+    ) {
+        let triggers = &cards.get(card).unwrap().triggers;
+        for trigger in triggers.iter().filter(|x| x.when == when) {
+            queue.push_back(QueuedEffect {
+                card,
+                slot: slot.clone(),
+                effect: trigger.effect,
+            });
+        }
+    }
+
+    // the start of turn triggers of the team's play row, left to right
+    pub fn queue_turn_start_triggers(
+        &self,
+        queue: &mut VecDeque<QueuedEffect>,
+        team: Team,
+        cards: &Assets<Card>,
+    ) {
+        for id in 0..CARD_SLOT_COUNT {
+            let slot = get_play_slot(team, id);
+            if let Some((card, _)) = self.get_card(&slot) {
+                self.queue_triggers(queue, TriggerKind::StartOfTurn, &slot, card, cards)
+            }
+        }
+    }
+
+    // the on play triggers of the cards the team placed this turn, left to right
+    pub fn queue_play_triggers(
+        &mut self,
+        queue: &mut VecDeque<QueuedEffect>,
+        team: Team,
+        cards: &Assets<Card>,
+    ) {
+        for id in 0..CARD_SLOT_COUNT {
+            let slot = get_play_slot(team, id);
+            let (card, stats) = match self.get_card(&slot) {
+                Some(x) if x.1.just_played => x,
+                _ => {
+                    continue;
+                }
+            };
+            self.queue_triggers(queue, TriggerKind::OnPlay, &slot, card, cards);
+            self.push_card_at(
+                slot,
+                card,
+                CardStats {
+                    just_played: false,
+                    ..stats
+                },
+            );
+        }
+    }
+
+    fn queue_death_triggers(&mut self, queue: &mut VecDeque<QueuedEffect>, cards: &Assets<Card>) {
+        for (slot, card, stats) in self.take_destroyed_cards() {
+            self.log(format!("{} was destroyed", cards.get(card).unwrap().name));
+            for equipment in stats.equipment.iter() {
+                self.log(format!("{} was discarded with it", equipment));
+            }
+            self.queue_triggers(queue, TriggerKind::OnDeath, &slot, card, cards);
+        }
+    }
+
+    // resolves the effects first in first out, anything they destroy queues its own triggers at the back
+    pub fn resolve_effects(
+        &mut self,
+        mut queue: VecDeque<QueuedEffect>,
+        cards: &Assets<Card>,
+        rng: &mut impl Rng,
+    ) {
+        self.queue_death_triggers(&mut queue, cards);
+        let mut resolved = 0;
+        while let Some(queued) = queue.pop_front() {
+            if resolved == MAX_RESOLVED_EFFECTS {
+                self.log(format!(
+                    "stopped a chain of triggers after {} effects",
+                    resolved
+                ));
+                break;
+            }
+            self.apply_effect(&queued, cards, rng);
+            self.queue_death_triggers(&mut queue, cards);
+            resolved += 1;
+        }
+    }
+
+    fn apply_effect(&mut self, queued: &QueuedEffect, cards: &Assets<Card>, rng: &mut impl Rng) {
+        let team = queued.slot.team;
+        let source = cards.get(queued.card).unwrap();
+        match queued.effect {
+            Effect::DamageOppositeLane(damage) => {
+                let target = get_play_slot(!team, queued.slot.id);
+                match self.get_card(&target) {
+                    Some((card, _)) => {
+                        self.log(format!(
+                            "{} dealt {} to {}",
+                            source.name,
+                            self.get_landed_damage(&target, damage),
+                            cards.get(card).unwrap().name
+                        ));
+                        self.damage_card(&target, damage);
+                    }
+                    None => {
+                        let health = self.get_team_health(!team);
+                        self.set_team_health(!team, health.saturating_sub(damage));
+                        self.log(format!(
+                            "{} dealt {} to {:?}",
+                            source.name,
+                            damage.min(health),
+                            !team
+                        ));
+                    }
+                }
+            }
+            Effect::HealAdjacent(amount) => {
+                for slot in get_neighbors(&queued.slot) {
+                    self.heal_card(&slot, amount, &source.name, cards);
+                }
+            }
+            Effect::DrawCard => {
+                let open_slot = match self.get_first_open_slot(team, CardSlotType::Hand) {
+                    Some(x) => x,
+                    None => {
+                        self.log(format!(
+                            "{} could not draw, {:?}'s hand is full",
+                            source.name, team
+                        ));
+                        return;
+                    }
+                };
+                let card = match self.get_random_card(rng) {
+                    Ok(x) => x,
+                    Err(err) => {
+                        self.log(format!("{} could not draw, {}", source.name, err));
+                        return;
+                    }
+                };
+                let card_asset = cards.get(card).unwrap();
+                self.push_card_into_stack(
+                    CardSlot {
+                        id: open_slot,
+                        team,
+                        slot_type: CardSlotType::Hand,
+                    },
+                    card,
+                    CardStats::new(card_asset),
+                );
+                self.stack_cards(team, CardSlotType::Hand);
+                self.log(format!(
+                    "{} drew {} for {:?}",
+                    source.name, card_asset.name, team
+                ));
+            }
+            Effect::BuffMatchingNeighbors(amount) => {
+                for slot in get_neighbors(&queued.slot) {
+                    let shares_color = match self.get_card(&slot) {
+                        Some((card, _)) => cards
+                            .get(card)
+                            .unwrap()
+                            .colors
+                            .iter()
+                            .any(|x| source.colors.contains(x)),
+                        None => false,
+                    };
+                    if shares_color {
+                        self.buff_card(
+                            &slot,
+                            Buff {
+                                hp: amount,
+                                ..default()
+                            },
+                            &source.name,
+                            cards,
+                        );
+                    }
+                }
+            }
+            Effect::StatusAdjacent(status) => {
+                for slot in get_neighbors(&queued.slot) {
+                    self.give_status(&slot, status, cards);
+                }
+            }
+            Effect::StatusOppositeLane(status) => {
+                self.give_status(&get_play_slot(!team, queued.slot.id), status, cards);
+            }
+        }
+    }
+
+    // healing stops at the card's max hp, and a frozen card gets none
+    fn heal_card(&mut self, slot: &CardSlot, amount: u32, source: &str, cards: &Assets<Card>) {
+        let (card, stats) = match self.get_card(slot) {
+            Some(x) if !x.1.has_status(StatusEffect::Frozen) => x,
+            _ => {
+                return;
+            }
+        };
+        let card_asset = cards.get(card).unwrap();
+        let hp = match (stats.hp, stats.get_max_hp()) {
+            (Some(hp), Some(max)) => (hp + amount).min(max.max(hp)),
+            _ => {
+                return;
+            }
+        };
+        if Some(hp) == stats.hp {
+            return;
+        }
+        self.log(format!(
+            "{} gave {} {} hp",
+            source,
+            card_asset.name,
+            hp - stats.hp.unwrap()
+        ));
+        self.push_card_at(
+            slot.clone(),
+            card,
+            CardStats {
+                hp: Some(hp),
+                ..stats
+            },
+        );
+    }
+
+    // a buff raises max hp along with hp, so it goes past what healing could reach
+    fn buff_card(&mut self, slot: &CardSlot, buff: Buff, source: &str, cards: &Assets<Card>) {
+        let (card, stats) = match self.get_card(slot) {
+            Some(x) if x.1.hp.is_some() => x,
+            _ => {
+                return;
+            }
+        };
+        self.log(format!(
+            "{} gave {} {} hp",
+            source,
+            cards.get(card).unwrap().name,
+            buff.hp
+        ));
+        let mut buffs = stats.buffs.clone();
+        buffs.push(buff);
+        self.push_card_at(
+            slot.clone(),
+            card,
+            CardStats {
+                hp: stats.hp.map(|x| x + buff.hp),
+                max_hp: stats.get_max_hp().map(|x| x + buff.hp),
+                buffs,
+                ..stats
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_state::TurnState;
+    use crate::rules::MatchRules;
+    use bevy::asset::ron;
+    use bevy_rand::prelude::WyRand;
+    use rand::SeedableRng;
+
+    const STARTING_HEALTH: u32 = 1000;
+
+    struct Board {
+        cards: Assets<Card>,
+        gc: GameController,
+    }
+
+    impl Board {
+        // red heroes as (slot, damage, hp, triggers), placed as if played this turn
+        fn new(placed: &[(CardSlot, u32, u32, &str)]) -> Self {
+            let mut cards = Assets::<Card>::default();
+            let mut board = vec![];
+            for (slot, damage, hp, triggers) in placed {
+                let card: Card = ron::de::from_str(&format!(
+                    "Card(name: \"{:?} {}\", colors: [Red], card_type: Hero, text: \"\", image: \"\", damage: Some({}), hp: Some({}), triggers: [{}])",
+                    slot.team, slot.id, damage, hp, triggers
+                ))
+                .unwrap();
+                let stats = CardStats {
+                    just_played: true,
+                    ..CardStats::new(&card)
+                };
+                board.push((slot.clone(), cards.add(card).id(), stats));
+            }
+            let rules = MatchRules {
+                starting_health: STARTING_HEALTH,
+                ..default()
+            };
+            let mut gc = GameController::empty(&cards, &rules);
+            for (slot, card, stats) in board {
+                gc.push_card_at(slot, card, stats);
+            }
+            Board { cards, gc }
+        }
+
+        fn resolve(&mut self, team: Team) {
+            self.gc.set_turn(team, TurnState::ApplyMoves);
+            self.gc
+                .resolve_moves(&self.cards, &mut WyRand::seed_from_u64(0));
+        }
+
+        fn resolve_rounds(&mut self, rounds: usize) {
+            for _ in 0..rounds {
+                self.resolve(Team::Red);
+                self.resolve(Team::Blue);
+            }
+        }
+
+        fn get_hp(&self, slot: &CardSlot) -> Option<u32> {
+            self.gc.get_card(slot).and_then(|x| x.1.hp)
+        }
+    }
+
+    #[test]
+    fn on_play_triggers_resolve_once() {
+        let mut board = Board::new(&[(
+            get_play_slot(Team::Red, 0),
+            0,
+            5,
+            "Trigger(when: OnPlay, effect: DamageOppositeLane(2))",
+        )]);
+        board.resolve_rounds(3);
+        assert_eq!(board.gc.get_team_health(Team::Blue), STARTING_HEALTH - 2);
+    }
+
+    #[test]
+    fn on_death_triggers_resolve_once() {
+        let attacker = get_play_slot(Team::Red, 0);
+        let mut board = Board::new(&[
+            (attacker.clone(), 5, 10, ""),
+            (
+                get_play_slot(Team::Blue, 0),
+                0,
+                1,
+                "Trigger(when: OnDeath, effect: DamageOppositeLane(3))",
+            ),
+        ]);
+        board.resolve(Team::Red);
+        assert_eq!(board.get_hp(&attacker), Some(7));
+        board.resolve_rounds(2);
+        assert_eq!(board.get_hp(&attacker), Some(7));
+    }
+
+    #[test]
+    fn long_chains_stop_at_the_effect_limit() {
+        let slot = get_play_slot(Team::Red, 0);
+        let mut board = Board::new(&[(slot.clone(), 0, 5, "")]);
+        let card = board.gc.get_card(&slot).unwrap().0;
+        // no card feeds itself forever yet, so the chain is queued up front
+        let queue = (0..MAX_RESOLVED_EFFECTS * 2)
+            .map(|_| QueuedEffect {
+                card,
+                slot: slot.clone(),
+                effect: Effect::DamageOppositeLane(1),
+            })
+            .collect();
+        board
+            .gc
+            .resolve_effects(queue, &board.cards, &mut WyRand::seed_from_u64(0));
+        assert_eq!(
+            board.gc.get_team_health(Team::Blue),
+            STARTING_HEALTH - MAX_RESOLVED_EFFECTS as u32
+        );
+    }
+
+    #[test]
+    fn healing_stops_at_max_hp() {
+        let healer = get_play_slot(Team::Red, 0);
+        let hurt = get_play_slot(Team::Red, 1);
+        let mut board = Board::new(&[
+            (
+                healer.clone(),
+                0,
+                5,
+                "Trigger(when: OnPlay, effect: HealAdjacent(4))",
+            ),
+            (hurt.clone(), 0, 5, ""),
+        ]);
+        let (card, stats) = board.gc.get_card(&hurt).unwrap();
+        board.gc.push_card_at(
+            hurt.clone(),
+            card,
+            CardStats {
+                hp: Some(3),
+                ..stats
+            },
+        );
+        board.resolve(Team::Red);
+        assert_eq!(board.get_hp(&hurt), Some(5));
+    }
+}
@@ -0,0 +1,154 @@
+use crate::assets::LoadState;
+use crate::game_state::{CurrentTurnTeam, TurnState};
+use crate::game_ui_controller::GameController;
+use bevy::prelude::*;
+
+const BANNER_SECONDS: f32 = 1.5;
+
+#[derive(Component, Clone, PartialEq, Eq, PartialOrd, Ord, Reflect)]
+pub struct TurnHudMarker;
+
+#[derive(Component, Clone, Debug)]
+pub struct TurnBanner {
+    timer: Timer,
+}
+
+fn spawn_turn_hud(mut commands: Commands, assets: Res<AssetServer>) {
+    let font: Handle<Font> = assets.load("ui/simple-pixel.ttf");
+    let text_style = TextStyle {
+        font: font.clone(),
+        font_size: 22.0,
+        color: Color::WHITE,
+    };
+    // turn number, team and phase are separate sections so the team can use its own color
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                top: Val::Px(10.0),
+                justify_content: JustifyContent::Center,
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+            z_index: ZIndex::Global(1),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn(TextBundle::from_sections([
+                    TextSection::new("", text_style.clone()),
+                    TextSection::new("", text_style.clone()),
+                    TextSection::new("", text_style.clone()),
+                ]))
+                .insert(TurnHudMarker);
+        });
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+            visibility: Visibility::Hidden,
+            z_index: ZIndex::Global(1),
+            ..default()
+        })
+        .insert(TurnBanner {
+            timer: Timer::from_seconds(BANNER_SECONDS, TimerMode::Once),
+        })
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "",
+                TextStyle {
+                    font,
+                    font_size: 64.0,
+                    color: Color::WHITE,
+                },
+            ));
+        });
+}
+
+fn update_turn_hud(
+    game_ui_controller_query: Query<&GameController>,
+    current_turn_team: Res<State<CurrentTurnTeam>>,
+    turn_state: Res<State<TurnState>>,
+    hud_added_query: Query<(), Added<TurnHudMarker>>,
+    mut hud_query: Query<&mut Text, With<TurnHudMarker>>,
+) {
+    if !current_turn_team.is_changed() && !turn_state.is_changed() && hud_added_query.is_empty() {
+        return;
+    }
+    let turn = match game_ui_controller_query.get_single() {
+        Ok(x) => x.get_turn(),
+        _ => {
+            return;
+        }
+    };
+    let team = current_turn_team.get().0;
+    for mut text in hud_query.iter_mut() {
+        text.sections[0].value = format!("Turn {}  ", turn);
+        text.sections[1].value = format!("{:?}", team);
+        text.sections[1].style.color = team.ui_color();
+        text.sections[2].value = format!("  {}", turn_state.get().get_phase_name());
+    }
+}
+
+fn show_turn_banner(
+    current_turn_team: Res<State<CurrentTurnTeam>>,
+    mut banner_query: Query<(&mut TurnBanner, &mut Visibility, &Children)>,
+    mut text_query: Query<&mut Text>,
+) {
+    let team = current_turn_team.get().0;
+    for (mut banner, mut visibility, children) in banner_query.iter_mut() {
+        banner.timer.reset();
+        *visibility = Visibility::Visible;
+        for child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(*child) {
+                text.sections[0].value = format!("{:?}'s turn", team);
+                text.sections[0].style.color = team.ui_color();
+            }
+        }
+    }
+}
+
+fn fade_turn_banner(
+    time: Res<Time>,
+    mut banner_query: Query<(&mut TurnBanner, &mut Visibility, &Children)>,
+    mut text_query: Query<&mut Text>,
+) {
+    for (mut banner, mut visibility, children) in banner_query.iter_mut() {
+        if *visibility == Visibility::Hidden {
+            continue;
+        }
+        if banner.timer.tick(time.delta()).finished() {
+            *visibility = Visibility::Hidden;
+            continue;
+        }
+        let alpha = banner.timer.fraction_remaining();
+        for child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(*child) {
+                text.sections[0].style.color.set_a(alpha);
+            }
+        }
+    }
+}
+
+pub struct TurnHudPlugin;
+
+impl Plugin for TurnHudPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(LoadState::Loaded), spawn_turn_hud)
+            .add_systems(
+                Update,
+                (
+                    update_turn_hud,
+                    show_turn_banner.run_if(state_changed::<CurrentTurnTeam>),
+                    fade_turn_banner,
+                )
+                    .chain(),
+            );
+    }
+}
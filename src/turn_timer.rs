@@ -0,0 +1,206 @@
+use crate::assets::LoadState;
+use crate::constants::CARD_SLOT_COUNT;
+use crate::game_state::{CardSlot, CardSlotType, PlayerActionEvent, TurnState};
+use crate::game_ui_controller::GameController;
+use crate::hot_seat::PassDevice;
+use crate::launch_options::LaunchOptions;
+use crate::protocol::PlayerAction;
+use bevy::prelude::*;
+
+const BAR_COLOR: Color = Color::rgb(1.0, 0.9, 0.35);
+const LOW_TIME_BAR_COLOR: Color = Color::rgb(0.9, 0.25, 0.2);
+const LOW_TIME_FRACTION: f32 = 0.25;
+
+// time spent in the current turn, kept on every process so clients can draw the countdown
+#[derive(Resource, Default, Clone, PartialEq, Debug)]
+pub struct TurnTimer {
+    pub turn: u32,
+    pub elapsed: f32,
+    expired: bool,
+}
+
+impl TurnTimer {
+    pub fn get_elapsed_ms(&self) -> u32 {
+        (self.elapsed * 1000.0) as u32
+    }
+}
+
+#[derive(Component, Clone, PartialEq, Eq, PartialOrd, Ord, Reflect)]
+pub struct TurnTimerBarMarker;
+
+fn spawn_turn_timer_bar(mut commands: Commands) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                left: Val::Px(0.0),
+                top: Val::Px(0.0),
+                width: Val::Percent(100.0),
+                height: Val::Px(6.0),
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+            background_color: BackgroundColor(Color::rgba(0.0, 0.0, 0.0, 0.5)),
+            visibility: Visibility::Hidden,
+            z_index: ZIndex::Global(1),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Percent(100.0),
+                        height: Val::Percent(100.0),
+                        ..default()
+                    },
+                    background_color: BackgroundColor(BAR_COLOR),
+                    ..default()
+                })
+                .insert(TurnTimerBarMarker);
+        });
+}
+
+fn tick_turn_timer(
+    time: Res<Time>,
+    pass_device: Res<PassDevice>,
+    mut turn_timer: ResMut<TurnTimer>,
+    mut game_ui_controller_query: Query<&mut GameController>,
+    launch_options: Res<LaunchOptions>,
+) {
+    let mut game_ui_controller = match game_ui_controller_query.get_single_mut() {
+        Ok(x) => x,
+        _ => {
+            return;
+        }
+    };
+    if turn_timer.turn != game_ui_controller.get_turn() {
+        // only the process running the rules reports the time, clients and replays would repeat it
+        if turn_timer.turn != 0
+            && !launch_options.is_network_client()
+            && !launch_options.is_replay()
+        {
+            game_ui_controller.log(format!(
+                "turn {} took {:.1}s",
+                turn_timer.turn, turn_timer.elapsed
+            ));
+        }
+        *turn_timer = TurnTimer {
+            turn: game_ui_controller.get_turn(),
+            ..default()
+        };
+    }
+    // the clock stops while a hot seat player is passing the device
+    if pass_device.waiting_for.is_none() {
+        turn_timer.elapsed += time.delta_seconds();
+    }
+}
+
+fn expire_turn(
+    mut turn_timer: ResMut<TurnTimer>,
+    mut game_ui_controller_query: Query<&mut GameController>,
+    launch_options: Res<LaunchOptions>,
+    mut player_actions: EventWriter<PlayerActionEvent>,
+) {
+    if launch_options.is_network_client() || launch_options.is_replay() {
+        return;
+    }
+    let mut game_ui_controller = match game_ui_controller_query.get_single_mut() {
+        Ok(x) => x,
+        _ => {
+            return;
+        }
+    };
+    let limit = match game_ui_controller.get_rules().turn_time_limit_seconds {
+        Some(x) => x as f32,
+        None => {
+            return;
+        }
+    };
+    if turn_timer.expired || turn_timer.elapsed < limit {
+        return;
+    }
+    let team = game_ui_controller.get_current_team();
+    let newest_hand_slot = (0..CARD_SLOT_COUNT).rev().find(|id| {
+        game_ui_controller
+            .get_card(&CardSlot {
+                id: *id,
+                team,
+                slot_type: CardSlotType::Hand,
+            })
+            .is_some()
+    });
+    let hand_full = game_ui_controller.card_stack_full(team, CardSlotType::Hand);
+    let actions = match game_ui_controller.get_turn_state() {
+        TurnState::Mulligan => vec![PlayerAction::Mulligan { hand_slots: vec![] }],
+        // a full hand loses its newest card so the draw can go through
+        TurnState::DrawCards => match newest_hand_slot {
+            Some(hand_slot) if hand_full => vec![
+                PlayerAction::Discard { hand_slot },
+                PlayerAction::Draw { pile: 0 },
+                PlayerAction::EndTurn,
+            ],
+            _ => vec![PlayerAction::Draw { pile: 0 }, PlayerAction::EndTurn],
+        },
+        TurnState::PlayCards => vec![PlayerAction::EndTurn],
+        TurnState::ApplyMoves => vec![],
+    };
+    turn_timer.expired = true;
+    game_ui_controller.log(format!("{:?} ran out of time", team));
+    for action in actions {
+        player_actions.send(PlayerActionEvent { team, action });
+    }
+}
+
+fn update_turn_timer_bar(
+    turn_timer: Res<TurnTimer>,
+    game_ui_controller_query: Query<&GameController>,
+    launch_options: Res<LaunchOptions>,
+    mut bar_query: Query<(&mut Style, &mut BackgroundColor, &Parent), With<TurnTimerBarMarker>>,
+    mut visibility_query: Query<&mut Visibility>,
+) {
+    let game_ui_controller = match game_ui_controller_query.get_single() {
+        Ok(x) => x,
+        _ => {
+            return;
+        }
+    };
+    let limit = match launch_options.is_replay() {
+        true => None,
+        false => game_ui_controller.get_rules().turn_time_limit_seconds,
+    };
+    for (mut style, mut background, parent) in bar_query.iter_mut() {
+        if let Ok(mut visibility) = visibility_query.get_mut(parent.get()) {
+            let wanted = match limit {
+                Some(_) => Visibility::Visible,
+                None => Visibility::Hidden,
+            };
+            if *visibility != wanted {
+                *visibility = wanted;
+            }
+        }
+        let limit = match limit {
+            Some(x) => x as f32,
+            None => {
+                continue;
+            }
+        };
+        let remaining = (1.0 - turn_timer.elapsed / limit).clamp(0.0, 1.0);
+        style.width = Val::Percent(remaining * 100.0);
+        background.0 = match remaining < LOW_TIME_FRACTION {
+            true => LOW_TIME_BAR_COLOR,
+            false => BAR_COLOR,
+        };
+    }
+}
+
+pub struct TurnTimerPlugin;
+
+impl Plugin for TurnTimerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TurnTimer>()
+            .add_systems(OnEnter(LoadState::Loaded), spawn_turn_timer_bar)
+            .add_systems(
+                Update,
+                (tick_turn_timer, expire_turn, update_turn_timer_bar).chain(),
+            );
+    }
+}